@@ -41,6 +41,28 @@ fn benchmarks(c: &mut Criterion) {
         })
     });
 
+    c.bench_function("classic-glyphs", |b| {
+        let font = badgen::notosans_font();
+        let mut font = badgen::font(&font);
+        let mut scratch = String::with_capacity(4098);
+        let mut out = String::with_capacity(4098);
+        let mut style = badgen::Style::classic();
+        style.text_rendering = badgen::TextRendering::Glyphs;
+
+        b.iter(|| {
+            out.clear();
+            badgen::write_badge_with_font(
+                &mut out,
+                &style,
+                black_box("world"),
+                black_box(Some("hello")),
+                &mut font,
+                &mut scratch,
+            )
+            .unwrap();
+        })
+    });
+
     c.bench_function("default-slow", |b| {
         b.iter(|| {
             badgen::badge(
@@ -53,5 +75,33 @@ fn benchmarks(c: &mut Criterion) {
     });
 }
 
+/// Reports the size of badges rendered with paths and with glyphs.
+fn sizes() {
+    let badges = [
+        ("build", "passing"),
+        ("license", "MIT"),
+        ("version", "1.2.3"),
+        ("coverage", "99%"),
+        ("downloads", "1.2M/month"),
+        ("docs", "passing"),
+        ("chat", "on discord"),
+        ("dependencies", "up to date"),
+    ];
+    println!("{:<28} {:>6} {:>6} {:>7}", "badge", "paths", "glyphs", "change");
+    for (label, status) in badges.iter() {
+        let mut style = badgen::Style::classic();
+        let paths = badgen::badge(&style, status, Some(label)).unwrap().len();
+        style.text_rendering = badgen::TextRendering::Glyphs;
+        let glyphs = badgen::badge(&style, status, Some(label)).unwrap().len();
+        println!(
+            "{:<28} {:>6} {:>6} {:>+6.1}%",
+            format!("{}/{}", label, status),
+            paths,
+            glyphs,
+            (glyphs as f64 / paths as f64 - 1.0) * 100.0
+        );
+    }
+}
+
 criterion_group!(benches, benchmarks);
-criterion_main!(benches);
+criterion_main!(benches, sizes);
@@ -0,0 +1,266 @@
+use alloc::string::String;
+use core::fmt::{self, Write};
+
+use crate::{Color, Colors, Style};
+
+/// The colors supported by a terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnsiColorMode {
+    /// 24-bit RGB colors.
+    #[default]
+    TrueColor,
+    /// The 256 color palette.
+    Ansi256,
+    /// The 16 standard and bright colors.
+    Ansi16,
+}
+
+/// Writes badges as ANSI colored text to a [`fmt::Write`].
+///
+/// Only the colors of a style are used, with each segment of the badge padded
+/// by a single space. Control characters in the text, such as escapes, are
+/// replaced with U+FFFD so the text can't inject its own terminal sequences.
+///
+/// # Example
+///
+/// ```rust
+/// let mut out = String::new();
+///
+/// badgen::AnsiWriter::new(&mut out)
+///     .write_badge(&badgen::Style::classic(), "passing", Some("build"))
+///     .unwrap();
+///
+/// println!("{}", out);
+/// ```
+#[derive(Debug)]
+pub struct AnsiWriter<W> {
+    w: W,
+    mode: AnsiColorMode,
+}
+
+impl<W> AnsiWriter<W>
+where
+    W: fmt::Write,
+{
+    /// Construct a new [`AnsiWriter`] using 24-bit colors.
+    pub fn new(w: W) -> Self {
+        Self::with_mode(w, AnsiColorMode::default())
+    }
+
+    /// Construct a new [`AnsiWriter`] with a given color mode.
+    pub fn with_mode(w: W, mode: AnsiColorMode) -> Self {
+        Self { w, mode }
+    }
+
+    /// Writes a badge given a style, status and optional label.
+    ///
+    /// Ghost styles are written without background colors.
+    pub fn write_badge<S>(
+        &mut self,
+        style: &Style<'_>,
+        status: S,
+        label: Option<&str>,
+    ) -> Result<&mut Self, fmt::Error>
+    where
+        S: fmt::Display,
+    {
+        let colors = Colors::new(style);
+        if let Some(label) = label {
+            self.write_segment(label, colors.label_background, colors.label_text)?;
+        }
+        self.write_segment(status, colors.status_background, colors.status_text)?;
+        Ok(self)
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+
+    fn write_segment<T>(
+        &mut self,
+        text: T,
+        background: Option<Color<'_>>,
+        text_color: Color<'_>,
+    ) -> fmt::Result
+    where
+        T: fmt::Display,
+    {
+        if let Some(rgb) = background.and_then(|c| c.to_rgb()) {
+            self.write_sgr(rgb, true)?;
+        }
+        if let Some(rgb) = text_color.to_rgb() {
+            self.write_sgr(rgb, false)?;
+        }
+        write!(ReplaceControl(&mut self.w), " {} ", text)?;
+        self.w.write_str("\x1b[0m")
+    }
+
+    fn write_sgr(&mut self, [r, g, b]: [u8; 3], background: bool) -> fmt::Result {
+        self.w.write_str("\x1b[")?;
+        match self.mode {
+            AnsiColorMode::TrueColor => {
+                self.w
+                    .write_str(if background { "48;2;" } else { "38;2;" })?;
+                itoa::fmt(&mut self.w, r)?;
+                self.w.write_char(';')?;
+                itoa::fmt(&mut self.w, g)?;
+                self.w.write_char(';')?;
+                itoa::fmt(&mut self.w, b)?;
+            }
+            AnsiColorMode::Ansi256 => {
+                self.w
+                    .write_str(if background { "48;5;" } else { "38;5;" })?;
+                itoa::fmt(&mut self.w, ansi256([r, g, b]))?;
+            }
+            AnsiColorMode::Ansi16 => {
+                let code = ansi16([r, g, b]);
+                itoa::fmt(&mut self.w, if background { code + 10 } else { code })?;
+            }
+        }
+        self.w.write_char('m')
+    }
+}
+
+/// Replaces control characters in written text with U+FFFD.
+struct ReplaceControl<'a, W>(&'a mut W);
+
+impl<'a, W> fmt::Write for ReplaceControl<'a, W>
+where
+    W: fmt::Write,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut last = 0;
+        for (i, c) in s.char_indices().filter(|(_, c)| c.is_control()) {
+            self.0.write_str(&s[last..i])?;
+            self.0.write_char(char::REPLACEMENT_CHARACTER)?;
+            last = i + c.len_utf8();
+        }
+        self.0.write_str(&s[last..])
+    }
+}
+
+/// Writes an ANSI colored badge to a `String` given a style, status and
+/// optional label.
+pub fn ansi_badge<S>(
+    style: &Style<'_>,
+    status: S,
+    label: Option<&str>,
+    mode: AnsiColorMode,
+) -> Result<String, fmt::Error>
+where
+    S: fmt::Display,
+{
+    let mut out = String::with_capacity(64);
+    AnsiWriter::with_mode(&mut out, mode).write_badge(style, status, label)?;
+    Ok(out)
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The standard xterm values of the 16 colors.
+const ANSI16_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [205, 0, 0],
+    [0, 205, 0],
+    [205, 205, 0],
+    [0, 0, 238],
+    [205, 0, 205],
+    [0, 205, 205],
+    [229, 229, 229],
+    [127, 127, 127],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [92, 92, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+];
+
+/// Returns the nearest color in the 256 color palette.
+fn ansi256([r, g, b]: [u8; 3]) -> u8 {
+    if r == g && g == b {
+        // Use the 24 step grayscale ramp.
+        return match r {
+            0..=7 => 16,
+            249..=255 => 231,
+            v => 232 + ((v as u16 - 8) * 24 / 241) as u8,
+        };
+    }
+    let cube = |v: u8| ((v as u16 * 5 + 127) / 255) as u8;
+    16 + 36 * cube(r) + 6 * cube(g) + cube(b)
+}
+
+/// Returns the foreground code of the nearest of the 16 colors.
+fn ansi16(rgb: [u8; 3]) -> u8 {
+    let distance = |c: &[u8; 3]| {
+        c.iter()
+            .zip(rgb.iter())
+            .map(|(&a, &b)| (a as i32 - b as i32).pow(2))
+            .sum::<i32>()
+    };
+    let (index, _) = ANSI16_PALETTE
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, c)| distance(c))
+        .unwrap();
+    if index < 8 {
+        30 + index as u8
+    } else {
+        90 + (index - 8) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi_writer() {
+        let mut out = String::new();
+        AnsiWriter::new(&mut out)
+            .write_badge(&Style::classic(), "passing", Some("build"))
+            .unwrap();
+        assert_eq!(
+            out,
+            "\x1b[48;2;85;85;85m\x1b[38;2;255;255;255m build \x1b[0m\
+             \x1b[48;2;0;136;204m\x1b[38;2;255;255;255m passing \x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_ansi_fallback() {
+        let mut out = String::new();
+        AnsiWriter::with_mode(&mut out, AnsiColorMode::Ansi16)
+            .write_badge(&Style::classic(), "passing", None)
+            .unwrap();
+        assert_eq!(out, "\x1b[46m\x1b[97m passing \x1b[0m");
+
+        let mut out = String::new();
+        AnsiWriter::with_mode(&mut out, AnsiColorMode::Ansi16)
+            .write_badge(&Style::ghost(), "passing", None)
+            .unwrap();
+        assert_eq!(out, "\x1b[36m passing \x1b[0m");
+
+        assert_eq!(ansi256([0, 0, 0]), 16);
+        assert_eq!(ansi256([255, 255, 255]), 231);
+        assert_eq!(ansi256([0x00, 0x88, 0xcc]), 38);
+    }
+
+    #[test]
+    fn test_ansi_control_chars() {
+        let out = ansi_badge(
+            &Style::ghost(),
+            "a\x1b]0;title\x07b",
+            Some("\r\n\u{9b}2J"),
+            AnsiColorMode::Ansi16,
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "\x1b[90m \u{FFFD}\u{FFFD}\u{FFFD}2J \x1b[0m\
+             \x1b[36m a\u{FFFD}]0;title\u{FFFD}b \x1b[0m"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! HTTP server for badges.
+//!
+//! Serves `/badge/...` paths as SVG badges, with a `/health` route for
+//! liveness checks.
+//!
+//! ```text
+//! badgen-server [--addr <ADDR>] [--max-age <SECONDS>] [--threads <COUNT>]
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+use std::{env, process, thread};
+
+use badgen::StyleFonts;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+const DEFAULT_MAX_AGE: u32 = 86400;
+
+struct Config {
+    addr: String,
+    max_age: u32,
+    threads: usize,
+}
+
+fn main() {
+    let config = parse_args().unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        process::exit(2);
+    });
+
+    let server = Server::http(&config.addr).unwrap_or_else(|err| {
+        eprintln!("error: failed to bind {}: {}", config.addr, err);
+        process::exit(1);
+    });
+
+    println!("listening on http://{}", server.server_addr());
+
+    thread::scope(|s| {
+        for _ in 0..config.threads {
+            s.spawn(|| {
+                // Each worker has its own glyph caches, so rendering isn't
+                // serialized across workers.
+                let mut fonts = StyleFonts::new();
+                let mut scratch = String::with_capacity(4096);
+                while let Ok(request) = server.recv() {
+                    handle(request, &config, &mut fonts, &mut scratch);
+                }
+            });
+        }
+    });
+}
+
+fn parse_args() -> Result<Config, String> {
+    let mut config = Config {
+        addr: DEFAULT_ADDR.to_owned(),
+        max_age: DEFAULT_MAX_AGE,
+        threads: thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for {}", name));
+        match arg.as_str() {
+            "--addr" => config.addr = value("--addr")?,
+            "--max-age" => {
+                config.max_age = value("--max-age")?
+                    .parse()
+                    .map_err(|_| "invalid value for --max-age")?
+            }
+            "--threads" => {
+                config.threads = value("--threads")?
+                    .parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or("invalid value for --threads")?
+            }
+            "-h" | "--help" => {
+                println!("usage: badgen-server [--addr <ADDR>] [--max-age <SECONDS>] [--threads <COUNT>]");
+                process::exit(0);
+            }
+            other => return Err(format!("unexpected argument {}", other)),
+        }
+    }
+    Ok(config)
+}
+
+fn handle(request: Request, config: &Config, fonts: &mut StyleFonts, scratch: &mut String) {
+    if !matches!(request.method(), Method::Get | Method::Head) {
+        let response = Response::from_string("method not allowed").with_status_code(405);
+        let _ = request.respond(response.with_header(header("Allow", "GET, HEAD")));
+        return;
+    }
+
+    let url = request.url();
+
+    if url == "/health" {
+        let _ = request.respond(Response::from_string("ok"));
+        return;
+    }
+
+    let badge = match badgen::url::parse(url) {
+        Ok(badge) => badge,
+        Err(badgen::url::UrlError::NotBadgePath) => {
+            let _ = request.respond(Response::from_string("not found").with_status_code(404));
+            return;
+        }
+        Err(err) => {
+            let _ = request.respond(Response::from_string(err.to_string()).with_status_code(400));
+            return;
+        }
+    };
+
+    let mut svg = String::with_capacity(8192);
+    if badge
+        .write_badge_with_font(&mut svg, fonts.get(&badge.style), scratch)
+        .is_err()
+    {
+        let _ = request.respond(Response::from_string("render failed").with_status_code(500));
+        return;
+    }
+
+    let etag = etag(&svg);
+    let cache_control = format!("public, max-age={}", config.max_age);
+
+    let not_modified = request
+        .headers()
+        .iter()
+        .filter(|h| h.field.equiv("If-None-Match"))
+        .flat_map(|h| h.value.as_str().split(','))
+        .any(|tag| tag.trim() == etag || tag.trim() == "*");
+
+    let response = if not_modified {
+        Response::from_data(Vec::new()).with_status_code(304)
+    } else {
+        Response::from_data(svg.into_bytes())
+            .with_header(header("Content-Type", "image/svg+xml; charset=utf-8"))
+    };
+
+    let _ = request.respond(
+        response
+            .with_header(header("ETag", &etag))
+            .with_header(header("Cache-Control", &cache_control)),
+    );
+}
+
+/// A strong entity tag of the response body using a 64-bit hash.
+fn etag(body: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(body.as_bytes());
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field, value).expect("valid header")
+}
@@ -0,0 +1,193 @@
+//! Command-line badge generator.
+//!
+//! ```text
+//! badgen --label build --status passing --color green --style flat -o build.svg
+//! badgen --json < spec.json
+//! badgen --batch badges.toml --out-dir data/generated
+//! ```
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::{env, fmt};
+
+use badgen::manifest::{self, Manifest, ManifestBadge};
+use badgen::StyleFonts;
+
+const USAGE: &str = "\
+usage: badgen [OPTIONS]
+
+options:
+    -l, --label <TEXT>          label text
+    -s, --status <TEXT>         status text
+    -c, --color <COLOR>         status background color
+        --label-color <COLOR>   label background color
+        --style <STYLE>         badge style (classic, flat, shields)
+    -o, --output <FILE>         output file (default: stdout)
+        --png                   output a PNG instead of an SVG
+        --scale <FACTOR>        PNG scale factor (default: 1)
+        --json                  read a JSON badge spec from stdin
+        --batch <FILE>          render a TOML or JSON badge manifest
+        --out-dir <DIR>         output directory for batch mode (default: .)
+    -h, --help                  print this help";
+
+#[derive(Default)]
+struct Args {
+    badge: ManifestBadge,
+    status: Option<String>,
+    png: bool,
+    scale: Option<f32>,
+    json: bool,
+    batch: Option<PathBuf>,
+    out_dir: Option<PathBuf>,
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let args = parse_args(env::args().skip(1))?;
+    let format = Format {
+        png: args.png,
+        scale: args.scale.unwrap_or(1.0),
+    };
+
+    let mut fonts = StyleFonts::new();
+
+    if let Some(batch) = args.batch {
+        let manifest = read_manifest(&batch)?;
+        let out_dir = args.out_dir.unwrap_or_else(|| PathBuf::from("."));
+        fs::create_dir_all(&out_dir)
+            .map_err(|err| format!("failed to create {}: {}", out_dir.display(), err))?;
+        if format.png {
+            manifest.validate().map_err(render_error)?;
+            for badge in &manifest.badges {
+                let png = render(badge, &format, &mut fonts)?;
+                let output = badge.output().map_err(render_error)?;
+                write_output(Some(&out_dir.join(output.as_ref())), &png)?;
+            }
+        } else {
+            for rendered in manifest::render_all(&manifest).map_err(render_error)? {
+                let output = rendered.badge.output().map_err(render_error)?;
+                write_output(
+                    Some(&out_dir.join(output.as_ref())),
+                    rendered.svg.as_bytes(),
+                )?;
+            }
+        }
+        return Ok(());
+    }
+
+    let badge = if args.json {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|err| format!("failed to read stdin: {}", err))?;
+        let mut badge: ManifestBadge =
+            serde_json::from_str(&input).map_err(|err| format!("invalid badge spec: {}", err))?;
+        // An output given as an argument takes precedence.
+        badge.output = args.badge.output.or(badge.output);
+        badge
+    } else {
+        ManifestBadge {
+            status: args.status.ok_or("missing --status")?,
+            ..args.badge
+        }
+    };
+
+    let output = render(&badge, &format, &mut fonts)?;
+    write_output(badge.output.as_deref().map(Path::new), &output)
+}
+
+fn parse_args<I>(mut args: I) -> Result<Args, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut parsed = Args::default();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "-l" | "--label" => parsed.badge.label = Some(value()?),
+            "-s" | "--status" => parsed.status = Some(value()?),
+            "-c" | "--color" => parsed.badge.color = Some(value()?),
+            "--label-color" => parsed.badge.label_color = Some(value()?),
+            "--style" => parsed.badge.style = Some(value()?),
+            "-o" | "--output" => parsed.badge.output = Some(value()?),
+            "--png" => parsed.png = true,
+            "--scale" => {
+                let scale = value()?;
+                let scale = scale
+                    .parse()
+                    .ok()
+                    .filter(|&s: &f32| s > 0.0)
+                    .ok_or(format!("invalid scale `{}`", scale))?;
+                parsed.scale = Some(scale);
+            }
+            "--json" => parsed.json = true,
+            "--batch" => parsed.batch = Some(value()?.into()),
+            "--out-dir" => parsed.out_dir = Some(value()?.into()),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            other => return Err(format!("unexpected argument `{}`\n\n{}", other, USAGE)),
+        }
+    }
+    Ok(parsed)
+}
+
+fn read_manifest(path: &Path) -> Result<Manifest, String> {
+    let manifest = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+    let invalid = |err: &dyn fmt::Display| format!("invalid manifest {}: {}", path.display(), err);
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str(&manifest).map_err(|err| invalid(&err))
+    } else {
+        serde_json::from_str(&manifest).map_err(|err| invalid(&err))
+    }
+}
+
+struct Format {
+    png: bool,
+    scale: f32,
+}
+
+fn render(
+    badge: &ManifestBadge,
+    format: &Format,
+    fonts: &mut StyleFonts,
+) -> Result<Vec<u8>, String> {
+    let style = badge.style().map_err(render_error)?;
+    let font = fonts.get(&style);
+    if format.png {
+        let label = badge.label.as_deref();
+        badgen::render_png_with_font(&style, &badge.status, label, font, format.scale)
+            .map_err(render_error)
+    } else {
+        let mut out = String::with_capacity(8192);
+        let mut scratch = String::with_capacity(4096);
+        badge
+            .write_badge_with_font(&mut out, font, &mut scratch)
+            .map_err(render_error)?;
+        Ok(out.into_bytes())
+    }
+}
+
+fn render_error<E: fmt::Display>(err: E) -> String {
+    format!("failed to render badge: {}", err)
+}
+
+fn write_output(path: Option<&Path>, badge: &[u8]) -> Result<(), String> {
+    match path {
+        Some(path) => fs::write(path, badge)
+            .map_err(|err| format!("failed to write {}: {}", path.display(), err)),
+        None => io::stdout()
+            .write_all(badge)
+            .map_err(|err| format!("failed to write stdout: {}", err)),
+    }
+}
@@ -0,0 +1,221 @@
+//! Compatibility with the [shields.io endpoint] JSON schema.
+//!
+//! [`Endpoint`] implements `Serialize` and `Deserialize`, and can be used with
+//! any serde format, such as `serde_json`.
+//!
+//! ```rust
+//! use badgen::endpoint::Endpoint;
+//!
+//! let json = r#"{"schemaVersion": 1, "label": "build", "message": "passing", "color": "success"}"#;
+//! let endpoint: Endpoint<'_> = serde_json::from_str(json).unwrap();
+//! let (style, status, label) = endpoint.to_badge().unwrap();
+//!
+//! let badge = badgen::badge(&style, status, label).unwrap();
+//!
+//! println!("{}", badge);
+//! ```
+//!
+//! [shields.io endpoint]: https://shields.io/endpoint
+
+use alloc::borrow::Cow;
+use alloc::string::ToString;
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Color, Style};
+
+/// The only supported endpoint schema version.
+pub const SCHEMA_VERSION: u8 = 1;
+
+/// A shields.io endpoint badge.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoint<'a> {
+    /// The schema version, which must be [`SCHEMA_VERSION`].
+    pub schema_version: u8,
+    /// The label of the badge, or empty for no label.
+    #[serde(borrow)]
+    pub label: Cow<'a, str>,
+    /// The status of the badge.
+    #[serde(borrow)]
+    pub message: Cow<'a, str>,
+    /// The status background color of the badge.
+    #[serde(default, borrow, skip_serializing_if = "Option::is_none")]
+    pub color: Option<Cow<'a, str>>,
+    /// The label background color of the badge.
+    #[serde(default, borrow, skip_serializing_if = "Option::is_none")]
+    pub label_color: Option<Cow<'a, str>>,
+    /// Whether the badge represents an error.
+    ///
+    /// Error badges default to a red status background.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub is_error: bool,
+    /// The name of a logo for the badge.
+    ///
+    /// Logos are not currently rendered.
+    #[serde(default, borrow, skip_serializing_if = "Option::is_none")]
+    pub named_logo: Option<Cow<'a, str>>,
+    /// The style of the badge.
+    ///
+    /// Styles are mapped as with [`style_for_name`](crate::url::style_for_name).
+    #[serde(default, borrow, skip_serializing_if = "Option::is_none")]
+    pub style: Option<Cow<'a, str>>,
+    /// How long the badge may be cached for, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_seconds: Option<u32>,
+}
+
+impl<'a> Endpoint<'a> {
+    /// Construct a new [`Endpoint`] with a label and status message.
+    pub fn new(label: &'a str, message: &'a str) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            label: Cow::Borrowed(label),
+            message: Cow::Borrowed(message),
+            color: None,
+            label_color: None,
+            is_error: false,
+            named_logo: None,
+            style: None,
+            cache_seconds: None,
+        }
+    }
+
+    /// Construct a new [`Endpoint`] from a style, status and optional label.
+    ///
+    /// Styles without a gradient or rounded corners are named `flat`, with
+    /// other styles left to the classic default.
+    pub fn from_badge(style: &Style<'a>, status: &'a str, label: Option<&'a str>) -> Self {
+        let flat = style.gradient.is_none() && style.border_radius == Style::flat().border_radius;
+        Self {
+            color: Some(color_str(style.background)),
+            label_color: style.label_background.map(color_str),
+            style: Some(Cow::Borrowed("flat")).filter(|_| flat),
+            ..Self::new(label.unwrap_or(""), status)
+        }
+    }
+
+    /// Converts the endpoint into a style, status and optional label.
+    pub fn to_badge(&self) -> Result<(Style<'_>, &str, Option<&str>), EndpointError> {
+        if self.schema_version != SCHEMA_VERSION {
+            return Err(EndpointError::UnsupportedSchemaVersion(self.schema_version));
+        }
+        let mut style = crate::url::style_for_name(self.style.as_deref().unwrap_or_default());
+        match self.color.as_deref() {
+            Some(color) => {
+                style.background =
+                    Color::parse_shields(color).ok_or(EndpointError::InvalidColor)?;
+            }
+            None if self.is_error => style.background = Color::Red,
+            None => {}
+        }
+        if let Some(label_color) = self.label_color.as_deref() {
+            let label_color =
+                Color::parse_shields(label_color).ok_or(EndpointError::InvalidLabelColor)?;
+            style.label_background = Some(label_color);
+        }
+        let label = Some(self.label.as_ref()).filter(|l| !l.is_empty());
+        Ok((style, self.message.as_ref(), label))
+    }
+}
+
+/// An error converting an [`Endpoint`] into a badge.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum EndpointError {
+    /// The schema version is not supported.
+    UnsupportedSchemaVersion(u8),
+    /// The status color is invalid.
+    InvalidColor,
+    /// The label color is invalid.
+    InvalidLabelColor,
+}
+
+impl fmt::Display for EndpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedSchemaVersion(v) => {
+                write!(f, "unsupported endpoint schema version {}", v)
+            }
+            Self::InvalidColor => f.write_str("invalid endpoint color"),
+            Self::InvalidLabelColor => f.write_str("invalid endpoint label color"),
+        }
+    }
+}
+
+fn color_str(color: Color<'_>) -> Cow<'_, str> {
+    match color.hex_str() {
+        Some(hex) => Cow::Borrowed(hex),
+        None => Cow::Owned(color.to_string()),
+    }
+}
+
+fn is_false(v: &bool) -> bool {
+    !*v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_to_badge() {
+        let json = r##"{
+            "schemaVersion": 1,
+            "label": "coverage",
+            "message": "93%",
+            "color": "#4c1",
+            "labelColor": "555",
+            "style": "flat",
+            "cacheSeconds": 300
+        }"##;
+        let endpoint: Endpoint<'_> = serde_json::from_str(json).unwrap();
+        assert_eq!(endpoint.cache_seconds, Some(300));
+        let (style, status, label) = endpoint.to_badge().unwrap();
+        assert_eq!(status, "93%");
+        assert_eq!(label, Some("coverage"));
+        assert_eq!(style.background, Color::Custom("4c1"));
+        assert_eq!(style.label_background, Some(Color::Custom("555")));
+        assert!(style.gradient.is_none());
+    }
+
+    #[test]
+    fn test_endpoint_errors() {
+        let json = r#"{"schemaVersion": 1, "label": "", "message": "oops", "isError": true}"#;
+        let endpoint: Endpoint<'_> = serde_json::from_str(json).unwrap();
+        let (style, _, label) = endpoint.to_badge().unwrap();
+        assert_eq!(style.background, Color::Red);
+        assert_eq!(label, None);
+
+        let mut endpoint = Endpoint::new("a", "b");
+        endpoint.color = Some(Cow::Borrowed("nope"));
+        assert_eq!(
+            endpoint.to_badge().unwrap_err(),
+            EndpointError::InvalidColor
+        );
+        endpoint.schema_version = 2;
+        assert_eq!(
+            endpoint.to_badge().unwrap_err(),
+            EndpointError::UnsupportedSchemaVersion(2)
+        );
+    }
+
+    #[test]
+    fn test_endpoint_from_badge() {
+        let endpoint = Endpoint::from_badge(&Style::classic(), "passing", Some("build"));
+        assert_eq!(
+            serde_json::to_string(&endpoint).unwrap(),
+            r#"{"schemaVersion":1,"label":"build","message":"passing","color":"08C","labelColor":"555"}"#
+        );
+
+        // The flat style round trips through its name.
+        let endpoint = Endpoint::from_badge(&Style::flat(), "passing", None);
+        assert_eq!(endpoint.style.as_deref(), Some("flat"));
+        let (style, _, label) = endpoint.to_badge().unwrap();
+        assert!(style.gradient.is_none());
+        assert_eq!(style.border_radius, Style::flat().border_radius);
+        assert_eq!(style.background.to_rgb(), Style::flat().background.to_rgb());
+        assert_eq!(label, None);
+    }
+}
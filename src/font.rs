@@ -1,14 +1,31 @@
 use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
 
-use ttf_parser::{Face as TrueTypeFontInner, OutlineBuilder};
+use ttf_parser::{Face as TrueTypeFontInner, FaceParsingError, OutlineBuilder, TableName};
 use uluru::LRUCache;
 
 use super::Point;
+use crate::{FontStyle, FontWeight};
 
 #[cfg(feature = "font-noto-sans")]
 const NOTOSANS_LICENSE: &str = include_str!("../data/fonts/notosans/LICENSE.txt");
 #[cfg(feature = "font-noto-sans")]
 const NOTOSANS_DATA: &[u8] = include_bytes!("../data/fonts/notosans/NotoSans-Regular.ttf");
+#[cfg(any(feature = "font-noto-sans-bold", feature = "font-noto-sans-italic"))]
+const NOTOSANS_OFL_LICENSE: &str = include_str!("../data/fonts/notosans/LICENSE_OFL.txt");
+#[cfg(feature = "font-noto-sans-bold")]
+const NOTOSANS_BOLD_DATA: &[u8] = include_bytes!("../data/fonts/notosans/NotoSans-Bold.ttf");
+#[cfg(feature = "font-noto-sans-italic")]
+const NOTOSANS_ITALIC_DATA: &[u8] = include_bytes!("../data/fonts/notosans/NotoSans-Italic.ttf");
+#[cfg(all(feature = "font-noto-sans-bold", feature = "font-noto-sans-italic"))]
+const NOTOSANS_BOLD_ITALIC_DATA: &[u8] =
+    include_bytes!("../data/fonts/notosans/NotoSans-BoldItalic.ttf");
+
+#[cfg(feature = "font-dejavu-sans")]
+const DEJAVUSANS_LICENSE: &str = include_str!("../data/fonts/dejavusans/LICENSE.txt");
+#[cfg(feature = "font-dejavu-sans")]
+const DEJAVUSANS_DATA: &[u8] = include_bytes!("../data/fonts/dejavusans/DejaVuSans.ttf");
 
 /// Parsed NotoSans font.
 #[cfg(feature = "font-noto-sans")]
@@ -16,14 +33,155 @@ pub fn notosans_font() -> ttf_parser::Face<'static> {
     ttf_parser::Face::from_slice(NOTOSANS_DATA, 0).unwrap()
 }
 
+/// Parsed NotoSans Bold font.
+#[cfg(feature = "font-noto-sans-bold")]
+pub fn notosans_bold_font() -> ttf_parser::Face<'static> {
+    ttf_parser::Face::from_slice(NOTOSANS_BOLD_DATA, 0).unwrap()
+}
+
+/// Parsed NotoSans Italic font.
+#[cfg(feature = "font-noto-sans-italic")]
+pub fn notosans_italic_font() -> ttf_parser::Face<'static> {
+    ttf_parser::Face::from_slice(NOTOSANS_ITALIC_DATA, 0).unwrap()
+}
+
+/// Parsed NotoSans Bold Italic font, included with both the
+/// `font-noto-sans-bold` and `font-noto-sans-italic` features.
+#[cfg(all(feature = "font-noto-sans-bold", feature = "font-noto-sans-italic"))]
+pub fn notosans_bold_italic_font() -> ttf_parser::Face<'static> {
+    ttf_parser::Face::from_slice(NOTOSANS_BOLD_ITALIC_DATA, 0).unwrap()
+}
+
+/// Parsed DejaVu Sans font.
+///
+/// Its metrics are close to Verdana, which shields.io badges are measured
+/// with.
+#[cfg(feature = "font-dejavu-sans")]
+pub fn dejavusans_font() -> ttf_parser::Face<'static> {
+    ttf_parser::Face::from_slice(DEJAVUSANS_DATA, 0).unwrap()
+}
+
+/// Parsed font of the family, weight and style of a badge style.
+///
+/// Families without a font included in binary fall back to NotoSans. Where the
+/// weight or style has no face included in binary, the nearest face is
+/// returned, and [`font_for_style`](crate::font_for_style) synthesizes the
+/// rest. The bold and italic faces of NotoSans are included with the
+/// `font-noto-sans-bold` and `font-noto-sans-italic` features. Without the
+/// `font-noto-sans` feature, DejaVu Sans is used for all families.
+///
+/// # Example
+///
+/// ```rust
+/// let mut style = badgen::Style::classic();
+/// style.font_weight = badgen::FontWeight::Bold;
+///
+/// let ttf_font = badgen::style_font(&style);
+/// let mut font = badgen::font_for_style(&ttf_font, &style);
+/// ```
+#[cfg(any(feature = "font-noto-sans", feature = "font-dejavu-sans"))]
+pub fn style_font(style: &crate::Style<'_>) -> ttf_parser::Face<'static> {
+    match (style.font_family, style.font_weight, style.font_style) {
+        #[cfg(feature = "font-dejavu-sans")]
+        (crate::FontFamily::DejaVuSans, _, _) => dejavusans_font(),
+        #[cfg(all(feature = "font-noto-sans-bold", feature = "font-noto-sans-italic"))]
+        (_, FontWeight::Bold, FontStyle::Italic) => notosans_bold_italic_font(),
+        #[cfg(feature = "font-noto-sans-bold")]
+        (_, FontWeight::Bold, _) => notosans_bold_font(),
+        #[cfg(feature = "font-noto-sans-italic")]
+        (_, _, FontStyle::Italic) => notosans_italic_font(),
+        #[cfg(feature = "font-noto-sans")]
+        _ => notosans_font(),
+        #[cfg(not(feature = "font-noto-sans"))]
+        _ => dejavusans_font(),
+    }
+}
+
 /// Licenses for fonts included in binary.
 pub fn font_licenses() -> &'static [&'static str] {
     &[
         #[cfg(feature = "font-noto-sans")]
         NOTOSANS_LICENSE,
+        #[cfg(any(feature = "font-noto-sans-bold", feature = "font-noto-sans-italic"))]
+        NOTOSANS_OFL_LICENSE,
+        #[cfg(feature = "font-dejavu-sans")]
+        DEJAVUSANS_LICENSE,
     ]
 }
 
+/// Parses font data for badge generation, given the index of the face within
+/// a collection.
+///
+/// TrueType (`.ttf`) and OpenType (`.otf`) fonts with TrueType or CFF
+/// outlines are supported, as are collections (`.ttc`) of them. WOFF and WOFF2
+/// fonts must first be decoded with `decode_woff`, behind the `woff` feature.
+///
+/// The font is checked for the tables used in rendering badges.
+///
+/// # Example
+///
+/// ```rust
+/// let data = include_bytes!("../data/fonts/notosans/NotoSans-Regular.ttf");
+/// let face = badgen::parse_font(data, 0).unwrap();
+/// let mut font = badgen::font(&face);
+/// ```
+pub fn parse_font(data: &[u8], index: u32) -> Result<ttf_parser::Face<'_>, FontError> {
+    match data.get(..4) {
+        Some(b"wOFF") | Some(b"wOF2") => return Err(FontError::UndecodedWoff),
+        _ => {}
+    }
+    let font = TrueTypeFontInner::from_slice(data, index).map_err(FontError::Parse)?;
+    if font.units_per_em().is_none() {
+        return Err(FontError::InvalidUnitsPerEm);
+    }
+    let required = [
+        (TableName::CharacterToGlyphIndexMapping, "cmap"),
+        (TableName::HorizontalMetrics, "hmtx"),
+    ];
+    for &(table, name) in required.iter() {
+        if !font.has_table(table) {
+            return Err(FontError::MissingTable(name));
+        }
+    }
+    let outlines = [
+        TableName::GlyphData,
+        TableName::CompactFontFormat,
+        TableName::CompactFontFormat2,
+    ];
+    if !outlines.iter().any(|&table| font.has_table(table)) {
+        return Err(FontError::MissingTable("glyf or CFF"));
+    }
+    Ok(font)
+}
+
+/// An error loading a font.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum FontError {
+    /// The font data failed to parse.
+    Parse(FaceParsingError),
+    /// The units per em of the font are missing or invalid.
+    InvalidUnitsPerEm,
+    /// A table used in rendering badges is missing.
+    MissingTable(&'static str),
+    /// The font is WOFF or WOFF2 and must be decoded first.
+    UndecodedWoff,
+    /// The WOFF or WOFF2 font is malformed.
+    MalformedWoff,
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "failed to parse font: {}", err),
+            Self::InvalidUnitsPerEm => f.write_str("font units per em are missing or invalid"),
+            Self::MissingTable(name) => write!(f, "font is missing the {} table", name),
+            Self::UndecodedWoff => f.write_str("WOFF font must be decoded first"),
+            Self::MalformedWoff => f.write_str("malformed WOFF font"),
+        }
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 
 /// A Font trait specific to badge generation.
@@ -39,10 +197,18 @@ pub trait Font {
         1.0
     }
 
-    /// The precision of the font used in path rendering.
+    /// The number of decimal places of coordinates used in path rendering.
     fn precision(&self) -> u8 {
         1
     }
+
+    /// Outline a character glyph in unscaled font units, returning `true` if
+    /// the glyph has an outline.
+    ///
+    /// This is used when rasterizing badges, and by default outlines nothing.
+    fn outline_glyph(&mut self, _c: char, _builder: &mut dyn OutlineBuilder) -> bool {
+        false
+    }
 }
 
 /// A rendered glyph.
@@ -116,35 +282,103 @@ where
     fn precision(&self) -> u8 {
         self.font.precision()
     }
+
+    fn outline_glyph(&mut self, c: char, builder: &mut dyn OutlineBuilder) -> bool {
+        self.font.outline_glyph(c, builder)
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/// How glyph paths are encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathEncoding {
+    /// Each segment is written with its own command.
+    #[default]
+    Standard,
+    /// The shortest encoding, using implicit command repetition, `h`/`v`
+    /// lines, `t`/`s` smooth curves and separators only where required.
+    ///
+    /// The geometry is identical to the standard encoding.
+    Minimal,
+}
+
 /// A True Type Font with a font height and path precision.
 #[derive(Debug, Clone)]
 pub struct TrueTypeFont<'a> {
-    font: &'a TrueTypeFontInner<'a>,
+    font: TrueTypeFontInner<'a>,
     scale: f32,
     height: u32,
     precision: u8,
+    encoding: PathEncoding,
+    /// The added stroke width synthesizing bold, in font units.
+    embolden: f32,
+    /// The horizontal shear synthesizing italic.
+    skew: f32,
     path_buffer: String,
 }
 
 impl<'a> TrueTypeFont<'a> {
     /// Construct a new [`TrueTypeFont`].
-    pub fn new(font: &'a TrueTypeFontInner<'a>, font_height: f32, precision: u8) -> Self {
+    pub fn new(font: &TrueTypeFontInner<'a>, font_height: f32, precision: u8) -> Self {
+        Self::with_encoding(font, font_height, precision, PathEncoding::default())
+    }
+
+    /// Construct a new [`TrueTypeFont`] from font data, given the index of the
+    /// face within a collection.
+    ///
+    /// See [`parse_font`] for the supported formats.
+    pub fn from_slice(
+        data: &'a [u8],
+        index: u32,
+        font_height: f32,
+        precision: u8,
+    ) -> Result<Self, FontError> {
+        let font = parse_font(data, index)?;
+        Ok(Self::new(&font, font_height, precision))
+    }
+
+    /// Construct a new [`TrueTypeFont`] with a given path encoding.
+    pub fn with_encoding(
+        font: &TrueTypeFontInner<'a>,
+        font_height: f32,
+        precision: u8,
+        encoding: PathEncoding,
+    ) -> Self {
         let units_per_em = font.units_per_em().expect("units-per-em not found") as f32;
         let scale = font_height / units_per_em;
         let height = font_height + (font.descender() as f32 * scale);
 
         Self {
-            font,
+            font: font.clone(),
             scale,
             precision,
+            encoding,
             height: height as u32,
+            embolden: 0.0,
+            skew: 0.0,
             path_buffer: String::default(),
         }
     }
+
+    /// Synthesizes a font weight and style the face does not have.
+    ///
+    /// Bold is synthesized by widening the strokes of glyphs, and italic by
+    /// slanting glyphs. Faces that are already bold or italic are left as is,
+    /// so prefer a real face where one is available, see [`style_font`].
+    pub fn synthesize(mut self, weight: FontWeight, style: FontStyle) -> Self {
+        self.embolden = match weight {
+            FontWeight::Bold if !self.font.is_bold() => {
+                self.font.units_per_em().unwrap_or(1000) as f32 * EMBOLDEN
+            }
+            _ => 0.0,
+        };
+        self.skew = match style {
+            FontStyle::Italic if !self.font.is_italic() && !self.font.is_oblique() => SKEW,
+            _ => 0.0,
+        };
+        self
+    }
 }
 
 impl<'a> Font for TrueTypeFont<'a> {
@@ -154,13 +388,21 @@ impl<'a> Font for TrueTypeFont<'a> {
 
     fn render_glyph(&mut self, c: char) -> Option<FontGlyph<'_>> {
         self.path_buffer.clear();
-        let mut sink = PathSink::new(self.scale, self.precision, &mut self.path_buffer);
+        let mut sink = PathSink::new(
+            self.scale,
+            self.precision,
+            self.encoding,
+            &mut self.path_buffer,
+        );
         if let Some(glyph_id) = self.font.glyph_index(c) {
             let hor_advance = self.font.glyph_hor_advance(glyph_id).unwrap();
-            let hor_advance = hor_advance as f32 * self.scale;
-            let path = match self.font.outline_glyph(glyph_id, &mut sink) {
-                Some(_) => Some(self.path_buffer.as_str()),
-                None => None,
+            let hor_advance = (hor_advance as f32 + self.embolden) * self.scale;
+            let has_outline =
+                outline_synthesized(&self.font, glyph_id, self.embolden, self.skew, &mut sink);
+            let path = if has_outline {
+                Some(self.path_buffer.as_str())
+            } else {
+                None
             };
             return Some(FontGlyph { path, hor_advance });
         }
@@ -174,33 +416,248 @@ impl<'a> Font for TrueTypeFont<'a> {
     fn precision(&self) -> u8 {
         self.precision
     }
+
+    fn outline_glyph(&mut self, c: char, builder: &mut dyn OutlineBuilder) -> bool {
+        match self.font.glyph_index(c) {
+            Some(glyph_id) => {
+                outline_synthesized(&self.font, glyph_id, self.embolden, self.skew, builder)
+            }
+            None => false,
+        }
+    }
+}
+
+/// The added stroke width of synthesized bold, relative to the em size.
+const EMBOLDEN: f32 = 1.0 / 20.0;
+
+/// The shear of synthesized italic, the tangent of 12 degrees.
+const SKEW: f32 = 0.2126;
+
+/// Outlines a glyph with a synthesized weight and style, returning `true` if
+/// the glyph has an outline.
+fn outline_synthesized(
+    font: &TrueTypeFontInner<'_>,
+    glyph_id: ttf_parser::GlyphId,
+    embolden: f32,
+    skew: f32,
+    builder: &mut dyn OutlineBuilder,
+) -> bool {
+    if embolden == 0.0 && skew == 0.0 {
+        return font.outline_glyph(glyph_id, builder).is_some();
+    }
+    let mut outline = Outline::default();
+    if font.outline_glyph(glyph_id, &mut outline).is_none() {
+        return false;
+    }
+    if embolden != 0.0 {
+        outline.embolden(embolden);
+    }
+    outline.write(builder, skew);
+    true
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Verb {
+    Move,
+    Line,
+    Quad,
+    Curve,
+    Close,
+}
+
+/// A recorded glyph outline in font units, for synthesizing weight and
+/// style.
+#[derive(Default)]
+struct Outline {
+    verbs: Vec<Verb>,
+    points: Vec<(f32, f32)>,
+    /// The index of the first point of each contour.
+    contours: Vec<usize>,
+}
+
+impl Outline {
+    fn contour_points(&self) -> impl Iterator<Item = &[(f32, f32)]> + '_ {
+        let ends = self.contours.iter().skip(1).copied();
+        let ends = ends.chain(Some(self.points.len()));
+        self.contours
+            .iter()
+            .zip(ends)
+            .map(move |(&start, end)| &self.points[start..end])
+    }
+
+    /// Widens every stroke of the outline by `strength`, moving each contour
+    /// outwards by half of it while keeping the left side bearing.
+    ///
+    /// Points are moved along the bisector of their adjacent edges, as done
+    /// by FreeType, so the outline keeps its number of points and grows
+    /// vertically as well as horizontally.
+    fn embolden(&mut self, strength: f32) {
+        // Outer contours run clockwise in TrueType outlines and
+        // counterclockwise in CFF outlines, which the sign of the total area
+        // tells apart.
+        let area: f32 = self
+            .contour_points()
+            .flat_map(|points| {
+                let next = points.iter().cycle().skip(1);
+                points.iter().zip(next).map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+            })
+            .sum();
+        let outward = if area < 0.0 { 1.0 } else { -1.0 };
+        let distance = strength / 2.0;
+
+        let mut points = Vec::with_capacity(self.points.len());
+        for contour in self.contour_points() {
+            for i in 0..contour.len() {
+                let (x, y) = embolden_point(contour, i, distance, outward);
+                points.push((x + distance, y));
+            }
+        }
+        self.points = points;
+    }
+
+    /// Writes the outline to a builder, shearing it by `skew`.
+    fn write(&self, builder: &mut dyn OutlineBuilder, skew: f32) {
+        let mut points = self.points.iter().map(|&(x, y)| (x + y * skew, y));
+        let mut next = || points.next().unwrap_or_default();
+        for verb in &self.verbs {
+            match verb {
+                Verb::Move => {
+                    let (x, y) = next();
+                    builder.move_to(x, y);
+                }
+                Verb::Line => {
+                    let (x, y) = next();
+                    builder.line_to(x, y);
+                }
+                Verb::Quad => {
+                    let ((x1, y1), (x, y)) = (next(), next());
+                    builder.quad_to(x1, y1, x, y);
+                }
+                Verb::Curve => {
+                    let ((x1, y1), (x2, y2), (x, y)) = (next(), next(), next());
+                    builder.curve_to(x1, y1, x2, y2, x, y);
+                }
+                Verb::Close => builder.close(),
+            }
+        }
+    }
+}
+
+/// Moves a point of a contour `distance` outwards, where `outward` is `1.0`
+/// for clockwise outer contours and `-1.0` otherwise.
+fn embolden_point(contour: &[(f32, f32)], i: usize, distance: f32, outward: f32) -> (f32, f32) {
+    let p = contour[i];
+    let len = contour.len();
+    // Neighbours at the same position, such as the end of a closed contour,
+    // are skipped.
+    let prev = (1..len)
+        .map(|k| contour[(i + len - k) % len])
+        .find(|&q| q != p);
+    let next = (1..len).map(|k| contour[(i + k) % len]).find(|&q| q != p);
+    let (prev, next) = match (prev, next) {
+        (Some(prev), Some(next)) => (prev, next),
+        _ => return p,
+    };
+    let unit = |(x, y): (f32, f32)| {
+        let len = (x * x + y * y).sqrt();
+        (x / len, y / len, len)
+    };
+    let (in_x, in_y, in_len) = unit((p.0 - prev.0, p.1 - prev.1));
+    let (out_x, out_y, out_len) = unit((next.0 - p.0, next.1 - p.1));
+
+    // One plus the cosine of the turn, near zero where the contour doubles
+    // back and the miter would spike.
+    let d = 1.0 + in_x * out_x + in_y * out_y;
+    if d < 1.0 / 16.0 {
+        return p;
+    }
+    // The sum of the outward normals of both edges, scaled to the miter of
+    // the offset edges.
+    let shift_x = -(in_y + out_y) * outward;
+    let shift_y = (in_x + out_x) * outward;
+    let mut scale = distance / d;
+    // Inner corners are limited by the shorter edge, so they don't cross.
+    let concave = (in_x * out_y - in_y * out_x) * outward > 0.0;
+    let miter = (shift_x * shift_x + shift_y * shift_y).sqrt() * scale;
+    let limit = in_len.min(out_len);
+    if concave && miter > limit {
+        scale *= limit / miter;
+    }
+    (p.0 + shift_x * scale, p.1 + shift_y * scale)
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.contours.push(self.points.len());
+        self.verbs.push(Verb::Move);
+        self.points.push((x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.verbs.push(Verb::Line);
+        self.points.push((x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.verbs.push(Verb::Quad);
+        self.points.extend_from_slice(&[(x1, y1), (x, y)]);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.verbs.push(Verb::Curve);
+        self.points.extend_from_slice(&[(x1, y1), (x2, y2), (x, y)]);
+    }
+
+    fn close(&mut self) {
+        self.verbs.push(Verb::Close);
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
 
 struct PathSink<'a> {
     scale: f32,
-    last: Point<f32>,
+    /// The last point written, rounded in units of the precision.
+    ///
+    /// Relative coordinates are calculated from the rounded position so
+    /// rounding errors don't accumulate along a path.
+    last: Point<i64>,
+    /// The start point of the current subpath, which closing returns to.
+    start: Point<i64>,
     path: &'a mut String,
-    f32_buf: ryu::Buffer,
     precision: u8,
     precision_mod: f32,
+    encoding: PathEncoding,
+    /// The command continued by numbers without a command letter.
+    implicit_command: u8,
+    /// The control point a `t` command would reflect, relative to the
+    /// current point.
+    quad_reflection: (i64, i64),
+    /// The control point an `s` command would reflect, relative to the
+    /// current point.
+    cubic_reflection: (i64, i64),
+    /// Whether a number was written since the last command letter.
+    after_number: bool,
+    /// Whether the last number written contains a decimal point.
+    after_decimal: bool,
 }
 
 impl<'a> PathSink<'a> {
-    fn new(scale: f32, precision: u8, path: &'a mut String) -> Self {
-        let precision_mod = if precision == 0 {
-            1.0
-        } else {
-            precision as f32 * 10.0
-        };
+    fn new(scale: f32, precision: u8, encoding: PathEncoding, path: &'a mut String) -> Self {
+        let precision = precision.min(MAX_PRECISION);
         Self {
             path,
             scale,
             precision,
-            precision_mod,
-            f32_buf: Default::default(),
-            last: Point { x: 0.0, y: 0.0 },
+            precision_mod: precision_mod(precision),
+            encoding,
+            last: Point { x: 0, y: 0 },
+            start: Point { x: 0, y: 0 },
+            implicit_command: 0,
+            quad_reflection: (0, 0),
+            cubic_reflection: (0, 0),
+            after_number: false,
+            after_decimal: false,
         }
     }
 
@@ -209,33 +666,55 @@ impl<'a> PathSink<'a> {
         self.path.push_str(s);
     }
 
+    /// Returns a scaled point rounded in units of the precision.
     #[inline]
-    fn write_x(&mut self, x: f32, first: bool) {
-        self.write_scaled_f32(x - self.last.x as f32, first)
+    fn round(&self, x: f32, y: f32) -> Point<i64> {
+        let round = |v: f32| (v * self.scale * self.precision_mod).round() as i64;
+        Point {
+            x: round(x),
+            y: round(y),
+        }
     }
 
+    /// Returns the coordinates of a rounded point relative to the last point,
+    /// with the `y` axis flipped.
     #[inline]
-    fn write_y(&mut self, y: f32) {
-        self.write_scaled_f32(self.last.y as f32 - y, false)
+    fn delta(&self, point: Point<i64>) -> [i64; 2] {
+        [point.x - self.last.x, self.last.y - point.y]
     }
 
     #[inline]
-    fn write_scaled_f32(&mut self, v: f32, first: bool) {
-        self.write_f32(v * self.scale, first);
+    fn write_f32(&mut self, v: f32, first: bool) {
+        self.write_rounded((v * self.precision_mod).round() as i64, first);
     }
 
+    /// Writes a value in units of the precision, with a separator unless it
+    /// is the first or negative.
     #[inline]
-    fn write_f32(&mut self, mut v: f32, first: bool) {
-        v = (v * self.precision_mod).round() / self.precision_mod;
-        if !first && v >= 0.0 {
+    fn write_rounded(&mut self, v: i64, first: bool) {
+        if !first && v >= 0 {
             self.write_str(" ");
         }
-        let vi32 = v as i32;
-        if self.precision == 0 || (v - vi32 as f32).abs() < f32::EPSILON {
-            itoa::fmt(&mut self.path, vi32).ok();
-        } else {
-            let s = self.f32_buf.format_finite(v);
-            self.path.push_str(s)
+        self.write_number(v);
+    }
+
+    /// Writes a value in units of the precision, without trailing zeros.
+    #[inline]
+    fn write_number(&mut self, v: i64) {
+        if self.precision == 0 {
+            itoa::fmt(&mut self.path, v).ok();
+            return;
+        }
+        let unit = 10i64.pow(self.precision as u32);
+        let (int, frac) = (v / unit, (v % unit).abs());
+        if v < 0 && int == 0 {
+            self.path.push('-');
+        }
+        itoa::fmt(&mut self.path, int).ok();
+        if frac != 0 {
+            write!(self.path, ".{:01$}", frac, self.precision as usize).ok();
+            let len = self.path.trim_end_matches('0').len();
+            self.path.truncate(len);
         }
     }
 
@@ -247,84 +726,748 @@ impl<'a> PathSink<'a> {
     }
 
     #[inline]
-    fn set_last(&mut self, x: f32, y: f32) {
-        self.last = Point { x, y };
+    fn set_last(&mut self, point: Point<i64>) {
+        self.last = point;
+    }
+
+    /// Writes a command with relative arguments in units of the precision.
+    fn write_command(&mut self, command: u8, args: &[i64]) {
+        match self.encoding {
+            PathEncoding::Standard => {
+                self.path.push(command as char);
+                for (i, &v) in args.iter().enumerate() {
+                    self.write_rounded(v, i == 0);
+                }
+            }
+            PathEncoding::Minimal => self.write_minimal_command(command, args),
+        }
+    }
+
+    fn write_minimal_command(&mut self, command: u8, args: &[i64]) {
+        // Reflections are relative to the end point of the segment.
+        let quad_reflection = match *args {
+            [x1, y1, x, y] if command == b'q' => (x - x1, y - y1),
+            _ => (0, 0),
+        };
+        let cubic_reflection = match *args {
+            [_, _, x2, y2, x, y] if command == b'c' => (x - x2, y - y2),
+            _ => (0, 0),
+        };
+
+        let (command, args) = match (command, args) {
+            (b'l', [_, 0]) => (b'h', &args[..1]),
+            (b'l', [0, _]) => (b'v', &args[1..]),
+            (b'q', [x1, y1, ..]) if (*x1, *y1) == self.quad_reflection => (b't', &args[2..]),
+            (b'c', [x1, y1, ..]) if (*x1, *y1) == self.cubic_reflection => (b's', &args[2..]),
+            _ => (command, args),
+        };
+
+        self.quad_reflection = quad_reflection;
+        self.cubic_reflection = cubic_reflection;
+
+        // Numbers following a command continue it, with a move continued by
+        // lines.
+        if command != self.implicit_command {
+            self.path.push(command as char);
+            self.after_number = false;
+        }
+        self.implicit_command = match command {
+            b'm' => b'l',
+            b'Z' => 0,
+            command => command,
+        };
+
+        for &v in args {
+            self.write_minimal_number(v);
+        }
+    }
+
+    /// Writes a value in units of the precision without a leading zero, and
+    /// with a separator only where the value would otherwise join the last.
+    fn write_minimal_number(&mut self, v: i64) {
+        let start = self.path.len();
+        self.write_number(v);
+
+        let sign = usize::from(v < 0);
+        if self.path[start + sign..].starts_with("0.") {
+            self.path.remove(start + sign);
+        }
+
+        let number = &self.path[start..];
+        let joins = number.starts_with('-') || (number.starts_with('.') && self.after_decimal);
+        self.after_decimal = number.contains('.');
+        if self.after_number && !joins {
+            self.path.insert(start, ' ');
+        }
+        self.after_number = true;
     }
 }
 
 impl<'a> OutlineBuilder for PathSink<'a> {
     #[inline]
     fn move_to(&mut self, x: f32, y: f32) {
-        self.write_str("m");
-        self.write_x(x, true);
-        self.write_y(y);
-        self.set_last(x, y);
+        let point = self.round(x, y);
+        self.write_command(b'm', &self.delta(point));
+        self.set_last(point);
+        self.start = point;
     }
 
     #[inline]
     fn line_to(&mut self, x: f32, y: f32) {
-        self.write_str("l");
-        self.write_x(x, true);
-        self.write_y(y);
-        self.set_last(x, y);
+        let point = self.round(x, y);
+        self.write_command(b'l', &self.delta(point));
+        self.set_last(point);
     }
 
     #[inline]
     fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
-        self.write_str("q");
-        self.write_x(x1, true);
-        self.write_y(y1);
-        self.write_x(x, false);
-        self.write_y(y);
-        self.set_last(x, y);
+        let [x1, y1] = self.delta(self.round(x1, y1));
+        let point = self.round(x, y);
+        let [x, y] = self.delta(point);
+        self.write_command(b'q', &[x1, y1, x, y]);
+        self.set_last(point);
     }
 
     #[inline]
     fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
-        self.write_str("c");
-        self.write_x(x1, true);
-        self.write_y(y1);
-        self.write_x(x2, false);
-        self.write_y(y2);
-        self.write_x(x, false);
-        self.write_y(y);
-        self.set_last(x, y);
+        let [x1, y1] = self.delta(self.round(x1, y1));
+        let [x2, y2] = self.delta(self.round(x2, y2));
+        let point = self.round(x, y);
+        let [x, y] = self.delta(point);
+        self.write_command(b'c', &[x1, y1, x2, y2, x, y]);
+        self.set_last(point);
     }
 
     #[inline]
     fn close(&mut self) {
-        self.write_str("Z");
+        self.write_command(b'Z', &[]);
+        self.set_last(self.start);
     }
 }
 
+/// The maximum number of decimal places of path coordinates.
+///
+/// Glyph outlines are `f32`, so have no further detail at badge sizes.
+const MAX_PRECISION: u8 = 4;
+
+/// The multiplier used to round values to a number of decimal places.
+fn precision_mod(precision: u8) -> f32 {
+    10f32.powi(precision.min(MAX_PRECISION) as i32)
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 
-pub(crate) fn render_text_path<T: Font>(
+pub(crate) fn render_text_path<T, D>(
     font: &mut T,
     origin: Point,
-    text: &str,
+    text: D,
     letter_spacing: f32,
     path_buffer: &mut String,
-) -> u32 {
-    let mut sink = PathSink::new(font.scale(), font.precision(), path_buffer);
+) -> Result<u32, fmt::Error>
+where
+    T: Font,
+    D: fmt::Display,
+{
+    let sink = PathSink::new(
+        font.scale(),
+        font.precision(),
+        PathEncoding::Standard,
+        path_buffer,
+    );
+    let letter_spacing = letter_spacing * font.scale();
+
+    let mut text_path = TextPathWrite {
+        sink,
+        font,
+        letter_spacing,
+        next_glyph_origin: Point {
+            x: origin.x as f32 + letter_spacing,
+            y: origin.y as f32,
+        },
+    };
+
+    write!(text_path, "{}", text)?;
+
+    Ok(text_path.next_glyph_origin.x as u32 - origin.x)
+}
+
+/// Renders the glyphs of text written to it, as it is written.
+struct TextPathWrite<'a, T> {
+    sink: PathSink<'a>,
+    font: &'a mut T,
+    letter_spacing: f32,
+    next_glyph_origin: Point<f32>,
+}
+
+impl<'a, T> fmt::Write for TextPathWrite<'a, T>
+where
+    T: Font,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            // TODO: can't render?
+            if let Some(entry) = self.font.render_glyph(c) {
+                if let Some(path) = entry.path {
+                    self.sink.set_last(Point { x: 0, y: 0 });
+                    self.sink.write_move_to_abs(self.next_glyph_origin);
+                    self.sink.write_str(path);
+                }
+                self.next_glyph_origin.x += entry.hor_advance + self.letter_spacing;
+            }
+        }
+        Ok(())
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The unique glyph paths used by rendered text, in order of first use.
+#[derive(Debug, Default)]
+pub(crate) struct GlyphSet {
+    glyphs: Vec<(char, String)>,
+}
+
+impl GlyphSet {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    /// Returns the glyph paths, indexed by [`GlyphUse::glyph`].
+    pub(crate) fn paths(&self) -> impl Iterator<Item = &str> {
+        self.glyphs.iter().map(|(_, path)| path.as_str())
+    }
+
+    fn insert(&mut self, c: char, path: &str) -> usize {
+        match self.glyphs.iter().position(|(glyph, _)| *glyph == c) {
+            Some(index) => index,
+            None => {
+                self.glyphs.push((c, path.into()));
+                self.glyphs.len() - 1
+            }
+        }
+    }
+}
+
+/// A glyph from a [`GlyphSet`] placed at a position in the viewbox.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GlyphUse {
+    pub(crate) glyph: usize,
+    pub(crate) origin: Point<f32>,
+}
+
+/// Renders text as glyph placements, adding any new glyph paths to the set.
+///
+/// Returns the width of the text, as with [`render_text_path`].
+pub(crate) fn render_text_glyphs<T, D>(
+    font: &mut T,
+    origin: Point,
+    text: D,
+    letter_spacing: f32,
+    glyphs: &mut GlyphSet,
+    uses: &mut Vec<GlyphUse>,
+) -> Result<u32, fmt::Error>
+where
+    T: Font,
+    D: fmt::Display,
+{
     let letter_spacing = letter_spacing * font.scale();
 
-    let mut next_glyph_origin = Point {
-        x: origin.x as f32 + letter_spacing,
-        y: origin.y as f32,
+    let mut text_glyphs = TextGlyphWrite {
+        precision_mod: precision_mod(font.precision()),
+        font,
+        glyphs,
+        uses,
+        letter_spacing,
+        next_glyph_origin: Point {
+            x: origin.x as f32 + letter_spacing,
+            y: origin.y as f32,
+        },
     };
 
-    for c in text.chars() {
-        // TODO: can't render?
-        if let Some(entry) = font.render_glyph(c) {
-            if let Some(path) = entry.path {
-                sink.set_last(0.0, 0.0);
-                sink.write_move_to_abs(next_glyph_origin);
-                sink.write_str(path);
+    write!(text_glyphs, "{}", text)?;
+
+    Ok(text_glyphs.next_glyph_origin.x as u32 - origin.x)
+}
+
+/// Places the glyphs of text written to it, as it is written.
+struct TextGlyphWrite<'a, T> {
+    font: &'a mut T,
+    glyphs: &'a mut GlyphSet,
+    uses: &'a mut Vec<GlyphUse>,
+    precision_mod: f32,
+    letter_spacing: f32,
+    next_glyph_origin: Point<f32>,
+}
+
+impl<'a, T> fmt::Write for TextGlyphWrite<'a, T>
+where
+    T: Font,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if let Some(entry) = self.font.render_glyph(c) {
+                if let Some(path) = entry.path {
+                    let precision_mod = self.precision_mod;
+                    let round = |v: f32| (v * precision_mod).round() / precision_mod;
+                    self.uses.push(GlyphUse {
+                        glyph: self.glyphs.insert(c, path),
+                        origin: Point {
+                            x: round(self.next_glyph_origin.x),
+                            y: round(self.next_glyph_origin.y),
+                        },
+                    });
+                }
+                self.next_glyph_origin.x += entry.hor_advance + self.letter_spacing;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses a path into absolute `M`, `L`, `Q`, `C` and `Z` segments.
+    fn parse_path(d: &str) -> Vec<(char, Vec<f32>)> {
+        let mut segments = Vec::new();
+        let bytes = d.as_bytes();
+        let mut i = 0;
+        let mut command = ' ';
+        let mut current = (0.0, 0.0);
+        let mut start = (0.0, 0.0);
+        let mut control: Option<(char, (f32, f32))> = None;
+
+        let number = |i: &mut usize| -> Option<f32> {
+            while *i < bytes.len() && bytes[*i] == b' ' {
+                *i += 1;
+            }
+            let begin = *i;
+            let mut dot = false;
+            while *i < bytes.len() {
+                match bytes[*i] {
+                    b'-' if *i == begin => {}
+                    b'0'..=b'9' => {}
+                    b'.' if !dot => dot = true,
+                    _ => break,
+                }
+                *i += 1;
+            }
+            d[begin..*i].parse().ok()
+        };
+
+        while i < bytes.len() {
+            if bytes[i].is_ascii_alphabetic() {
+                command = bytes[i] as char;
+                i += 1;
+                if command == 'Z' {
+                    current = start;
+                    control = None;
+                    segments.push(('Z', Vec::new()));
+                    continue;
+                }
+            }
+            let arity = match command.to_ascii_lowercase() {
+                'h' | 'v' => 1,
+                'm' | 'l' | 't' => 2,
+                'q' | 's' => 4,
+                'c' => 6,
+                c => panic!("unexpected command {:?} in {}", c, d),
+            };
+            let mut args = Vec::new();
+            for _ in 0..arity {
+                args.push(number(&mut i).expect(d));
             }
-            next_glyph_origin.x += entry.hor_advance + letter_spacing;
+            let (x, y) = current;
+            let point = |dx: f32, dy: f32| {
+                if command.is_ascii_uppercase() {
+                    (dx, dy)
+                } else {
+                    (x + dx, y + dy)
+                }
+            };
+            let reflect = |kind: char| match control {
+                Some((k, (cx, cy))) if k == kind => (2.0 * x - cx, 2.0 * y - cy),
+                _ => current,
+            };
+            let (name, points) = match command.to_ascii_lowercase() {
+                'm' => {
+                    start = point(args[0], args[1]);
+                    // Subsequent pairs are lines.
+                    command = if command == 'm' { 'l' } else { 'L' };
+                    ('M', [start].to_vec())
+                }
+                'l' => ('L', [point(args[0], args[1])].to_vec()),
+                'h' => ('L', [(point(args[0], 0.0).0, y)].to_vec()),
+                'v' => ('L', [(x, point(0.0, args[0]).1)].to_vec()),
+                'q' => (
+                    'Q',
+                    [point(args[0], args[1]), point(args[2], args[3])].to_vec(),
+                ),
+                't' => ('Q', [reflect('Q'), point(args[0], args[1])].to_vec()),
+                'c' => (
+                    'C',
+                    [
+                        point(args[0], args[1]),
+                        point(args[2], args[3]),
+                        point(args[4], args[5]),
+                    ]
+                    .to_vec(),
+                ),
+                's' => (
+                    'C',
+                    [
+                        reflect('C'),
+                        point(args[0], args[1]),
+                        point(args[2], args[3]),
+                    ]
+                    .to_vec(),
+                ),
+                _ => unreachable!(),
+            };
+            current = *points.last().unwrap();
+            control = match name {
+                'Q' | 'C' => Some((name, points[points.len() - 2])),
+                _ => None,
+            };
+            segments.push((name, points.iter().flat_map(|&(x, y)| [x, y]).collect()));
         }
+        segments
     }
 
-    next_glyph_origin.x as u32 - origin.x
+    fn assert_same_geometry(standard: &str, minimal: &str) {
+        let (standard, minimal) = (parse_path(standard), parse_path(minimal));
+        assert_eq!(standard.len(), minimal.len());
+        for (a, b) in standard.iter().zip(minimal.iter()) {
+            assert_eq!(a.0, b.0);
+            for (a, b) in a.1.iter().zip(b.1.iter()) {
+                assert!((a - b).abs() < 1e-3, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    /// Records an outline as absolute segments in path coordinates.
+    #[cfg(feature = "font-noto-sans")]
+    struct OutlineRecorder {
+        scale: f32,
+        segments: Vec<(char, Vec<f32>)>,
+    }
+
+    #[cfg(feature = "font-noto-sans")]
+    impl OutlineRecorder {
+        fn push(&mut self, name: char, points: &[f32]) {
+            let scale = self.scale;
+            let points = points.chunks(2).flat_map(|p| [p[0] * scale, -p[1] * scale]);
+            self.segments.push((name, points.collect()));
+        }
+    }
+
+    #[cfg(feature = "font-noto-sans")]
+    impl OutlineBuilder for OutlineRecorder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            self.push('M', &[x, y]);
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            self.push('L', &[x, y]);
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            self.push('Q', &[x1, y1, x, y]);
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            self.push('C', &[x1, y1, x2, y2, x, y]);
+        }
+
+        fn close(&mut self) {
+            self.push('Z', &[]);
+        }
+    }
+
+    #[cfg(feature = "font-noto-sans")]
+    #[test]
+    fn test_precision_outlines() {
+        let face = notosans_font();
+        for precision in 0..=3 {
+            let mut font = TrueTypeFont::new(&face, 1100.0, precision);
+            let tolerance = 0.5 / 10f32.powi(precision as i32) + 1e-3;
+            for c in (' '..='~').chain("éßΩ€".chars()) {
+                let path = String::from(font.render_glyph(c).unwrap().path.unwrap_or(""));
+
+                // Coordinates have at most `precision` decimal places.
+                for number in path.split(|c: char| c.is_ascii_alphabetic() || c == ' ' || c == '-')
+                {
+                    let decimals = number.split_once('.').map_or(0, |(_, d)| d.len());
+                    assert!(decimals <= precision as usize, "{} in {:?}", number, c);
+                }
+
+                // Every point is within rounding of the exact outline.
+                let mut exact = OutlineRecorder {
+                    scale: font.scale(),
+                    segments: Vec::new(),
+                };
+                face.outline_glyph(face.glyph_index(c).unwrap(), &mut exact);
+                let rendered = parse_path(&path);
+                assert_eq!(rendered.len(), exact.segments.len(), "{:?}", c);
+                for (a, b) in rendered.iter().zip(exact.segments.iter()) {
+                    assert_eq!(a.0, b.0);
+                    for (a, b) in a.1.iter().zip(b.1.iter()) {
+                        assert!((a - b).abs() <= tolerance, "{} != {} in {:?}", a, b, c);
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "font-noto-sans")]
+    #[test]
+    fn test_closed_contour_endpoints() {
+        // Rounding errors accumulated along a contour are most visible at
+        // large sizes.
+        let face = notosans_font();
+        let mut font = TrueTypeFont::new(&face, 110_000.0, 0);
+        for c in ('a'..='z').chain('A'..='Z').chain('0'..='9') {
+            let path = String::from(font.render_glyph(c).unwrap().path.unwrap());
+            let mut exact = OutlineRecorder {
+                scale: font.scale(),
+                segments: Vec::new(),
+            };
+            face.outline_glyph(face.glyph_index(c).unwrap(), &mut exact);
+            let rendered = parse_path(&path);
+            assert_eq!(rendered.len(), exact.segments.len(), "{:?}", c);
+
+            let end = |segments: &[(char, Vec<f32>)], i: usize| {
+                let points = &segments[i].1;
+                (points[points.len() - 2], points[points.len() - 1])
+            };
+            let mut start = 0;
+            for (i, (name, _)) in exact.segments.iter().enumerate() {
+                match name {
+                    'M' => start = i,
+                    'Z' => {
+                        let (x, y) = end(&rendered, i - 1);
+                        let (exact_x, exact_y) = end(&exact.segments, i - 1);
+                        assert!(
+                            (x - exact_x).abs() <= 0.5,
+                            "{} != {} in {:?}",
+                            x,
+                            exact_x,
+                            c
+                        );
+                        assert!(
+                            (y - exact_y).abs() <= 0.5,
+                            "{} != {} in {:?}",
+                            y,
+                            exact_y,
+                            c
+                        );
+                        // Contours closed in the font are closed exactly.
+                        if (exact_x, exact_y) == end(&exact.segments, start) {
+                            assert_eq!((x, y), end(&rendered, start), "{:?}", c);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_close_returns_to_start() {
+        for encoding in [PathEncoding::Standard, PathEncoding::Minimal]
+            .iter()
+            .copied()
+        {
+            let mut path = String::new();
+            let mut sink = PathSink::new(1.0, 0, encoding, &mut path);
+            sink.move_to(5.0, 0.0);
+            sink.line_to(15.0, 0.0);
+            sink.close();
+            sink.move_to(25.0, 0.0);
+            assert!(path.ends_with("Zm20 0"), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_precision_decimal_places() {
+        let path = |precision, x: f32| {
+            let mut path = String::new();
+            let mut sink = PathSink::new(1.0, precision, PathEncoding::Standard, &mut path);
+            sink.move_to(x, -x);
+            path
+        };
+        assert_eq!(path(0, 1.2345), "m1 1");
+        assert_eq!(path(1, 1.2345), "m1.2 1.2");
+        assert_eq!(path(2, 1.2345), "m1.23 1.23");
+        assert_eq!(path(2, -0.5), "m-0.5-0.5");
+        assert_eq!(path(2, 0.5), "m0.5 0.5");
+        assert_eq!(path(3, 2.0), "m2 2");
+        assert_eq!(path(9, 0.123456), "m0.1235 0.1235");
+    }
+
+    #[cfg(feature = "font-noto-sans")]
+    #[test]
+    fn test_minimal_encoding_glyphs() {
+        let face = notosans_font();
+        for precision in [0, 1, 2].iter().copied() {
+            let mut standard = TrueTypeFont::new(&face, 1100.0, precision);
+            let mut minimal =
+                TrueTypeFont::with_encoding(&face, 1100.0, precision, PathEncoding::Minimal);
+            let (mut standard_len, mut minimal_len) = (0, 0);
+            for c in (' '..='~').chain("éßΩ€".chars()) {
+                let standard = String::from(standard.render_glyph(c).unwrap().path.unwrap_or(""));
+                let minimal = minimal.render_glyph(c).unwrap().path.unwrap_or("");
+                assert_same_geometry(&standard, minimal);
+                standard_len += standard.len();
+                minimal_len += minimal.len();
+            }
+            assert!(minimal_len * 20 < standard_len * 19, "{}", precision);
+        }
+    }
+
+    #[cfg(feature = "font-noto-sans")]
+    #[test]
+    fn test_synthesized_weight_and_style() {
+        let face = notosans_font();
+        let outline = |weight, style, c| {
+            let mut font = TrueTypeFont::new(&face, 1100.0, 0).synthesize(weight, style);
+            let mut recorder = OutlineRecorder {
+                scale: 1.0,
+                segments: Vec::new(),
+            };
+            assert!(font.outline_glyph(c, &mut recorder));
+            let hor_advance = font.render_glyph(c).unwrap().hor_advance;
+            (recorder.segments, hor_advance)
+        };
+        let (regular, regular_advance) = outline(FontWeight::Normal, FontStyle::Normal, 'l');
+
+        // Bold widens the strokes of the outline, keeping its left side
+        // bearing and number of segments, and adds the width to the advance.
+        let (bold, bold_advance) = outline(FontWeight::Bold, FontStyle::Normal, 'l');
+        let embolden = face.units_per_em().unwrap() as f32 * EMBOLDEN;
+        let bounds = |segments: &[(char, Vec<f32>)]| {
+            let mut bounds = [f32::MAX, f32::MAX, f32::MIN, f32::MIN];
+            for p in segments.iter().flat_map(|s| s.1.chunks(2)) {
+                bounds = [
+                    bounds[0].min(p[0]),
+                    bounds[1].min(p[1]),
+                    bounds[2].max(p[0]),
+                    bounds[3].max(p[1]),
+                ];
+            }
+            bounds
+        };
+        let (regular_bounds, bold_bounds) = (bounds(&regular), bounds(&bold));
+        assert_eq!(bold.len(), regular.len());
+        let grown = [0.0, embolden / 2.0, embolden, embolden / 2.0];
+        for i in 0..4 {
+            let growth = (bold_bounds[i] - regular_bounds[i]).abs();
+            assert!((growth - grown[i]).abs() < 1e-3, "{}", i);
+        }
+        let scale = TrueTypeFont::new(&face, 1100.0, 0).scale();
+        assert!((bold_advance - regular_advance - embolden * scale).abs() < 1e-3);
+
+        // Italic slants the outline without changing the advance.
+        let (italic, italic_advance) = outline(FontWeight::Normal, FontStyle::Italic, 'l');
+        assert_eq!(italic.len(), regular.len());
+        assert_eq!(italic_advance, regular_advance);
+        for (a, b) in regular.iter().zip(italic.iter()) {
+            for (a, b) in a.1.chunks(2).zip(b.1.chunks(2)) {
+                assert!((a[0] - a[1] * SKEW - b[0]).abs() < 1e-3);
+                assert_eq!(a[1], b[1]);
+            }
+        }
+    }
+
+    #[cfg(feature = "font-noto-sans")]
+    #[test]
+    fn test_parse_font_collection() {
+        // A collection of the same face twice, with table offsets shifted
+        // past the collection header.
+        let num_tables = u16::from_be_bytes([NOTOSANS_DATA[4], NOTOSANS_DATA[5]]) as usize;
+        let directory_len = 12 + num_tables * 16;
+        let header_len = 20 + 2 * directory_len;
+        let mut directory = NOTOSANS_DATA[..directory_len].to_vec();
+        for record in directory[12..].chunks_mut(16) {
+            let offset = u32::from_be_bytes([record[8], record[9], record[10], record[11]]);
+            record[8..12].copy_from_slice(&(offset + header_len as u32).to_be_bytes());
+        }
+        let mut ttc = Vec::new();
+        ttc.extend_from_slice(b"ttcf\x00\x01\x00\x00");
+        for v in [2, 20, 20 + directory_len as u32].iter() {
+            ttc.extend_from_slice(&v.to_be_bytes());
+        }
+        ttc.extend_from_slice(&directory);
+        ttc.extend_from_slice(&directory);
+        ttc.extend_from_slice(NOTOSANS_DATA);
+
+        let face = parse_font(&ttc, 1).unwrap();
+        let mut font = TrueTypeFont::new(&face, 1100.0, 0);
+        let mut expected = TrueTypeFont::from_slice(NOTOSANS_DATA, 0, 1100.0, 0).unwrap();
+        let path = String::from(font.render_glyph('b').unwrap().path.unwrap());
+        assert_eq!(expected.render_glyph('b').unwrap().path.unwrap(), path);
+        assert_eq!(
+            parse_font(&ttc, 2).unwrap_err(),
+            FontError::Parse(FaceParsingError::FaceIndexOutOfBounds)
+        );
+    }
+
+    #[cfg(feature = "font-noto-sans")]
+    #[test]
+    fn test_parse_font_errors() {
+        use alloc::string::ToString;
+
+        assert_eq!(
+            parse_font(b"wOF2\x00\x01\x00\x00", 0).unwrap_err(),
+            FontError::UndecodedWoff
+        );
+        assert_eq!(
+            parse_font(b"nope", 0).unwrap_err(),
+            FontError::Parse(FaceParsingError::UnknownMagic)
+        );
+
+        // Renaming the cmap table hides it.
+        let mut data = NOTOSANS_DATA.to_vec();
+        let cmap = data.windows(4).position(|w| w == b"cmap").unwrap();
+        data[cmap..cmap + 4].copy_from_slice(b"cmaq");
+        let err = TrueTypeFont::from_slice(&data, 0, 1100.0, 0).unwrap_err();
+        assert_eq!(err, FontError::MissingTable("cmap"));
+        assert_eq!(err.to_string(), "font is missing the cmap table");
+    }
+
+    #[test]
+    fn test_minimal_encoding_commands() {
+        let outline = |encoding| {
+            let mut path = String::new();
+            let mut sink = PathSink::new(1.0, 1, encoding, &mut path);
+            sink.move_to(0.0, 0.0);
+            sink.line_to(10.0, 0.0);
+            sink.line_to(20.0, 0.0);
+            sink.line_to(20.0, -10.0);
+            sink.quad_to(25.0, -15.0, 30.0, -10.0);
+            sink.quad_to(35.0, -5.0, 40.0, -10.0);
+            sink.curve_to(40.5, -20.0, 50.0, -20.0, 50.0, -10.0);
+            sink.curve_to(50.0, 0.0, 40.25, 0.0, 40.0, -10.0);
+            sink.line_to(40.5, -10.5);
+            sink.line_to(0.0, 0.0);
+            sink.close();
+            sink.move_to(5.0, 5.0);
+            sink.line_to(6.0, 7.0);
+            sink.close();
+            path
+        };
+        let standard = outline(PathEncoding::Standard);
+        let minimal = outline(PathEncoding::Minimal);
+        assert_eq!(
+            standard,
+            "m0 0l10 0l10 0l0 10q5 5 10 0q5-5 10 0c0.5 10 10 10 10 0c0-10-9.7-10-10 0\
+             l0.5 0.5l-40.5-10.5Zm5-5l1-2Z"
+        );
+        assert_eq!(
+            minimal,
+            "m0 0h10 10v10q5 5 10 0t10 0c.5 10 10 10 10 0s-9.7-10-10 0l.5.5-40.5-10.5Zm5-5 1-2Z"
+        );
+        assert_same_geometry(&standard, &minimal);
+    }
 }
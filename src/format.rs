@@ -0,0 +1,283 @@
+//! Formatters for common badge status values.
+//!
+//! Each formatter implements [`fmt::Display`] and can be passed directly as
+//! the status of a badge, in which case it is rendered straight into the text
+//! path without an intermediate `String`.
+//!
+//! ```rust
+//! use badgen::format::Bytes;
+//!
+//! let badge = badgen::badge(
+//!     &badgen::Style::classic(),
+//!     Bytes::si(4200),
+//!     Some("minzipped size"),
+//! ).unwrap();
+//!
+//! println!("{}", badge);
+//! ```
+
+use core::fmt::{self, Write};
+use core::time::Duration as StdDuration;
+
+const SI_BYTE_UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB", "PB", "EB"];
+const IEC_BYTE_UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+const COUNT_UNITS: &[&str] = &["", "k", "M", "G", "T", "P", "E"];
+
+/// A human readable byte size, such as `4.2 KB` or `4.1 KiB`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bytes {
+    value: u64,
+    iec: bool,
+}
+
+impl Bytes {
+    /// A byte size using SI units (powers of 1000).
+    pub const fn si(value: u64) -> Self {
+        Self { value, iec: false }
+    }
+
+    /// A byte size using IEC units (powers of 1024).
+    pub const fn iec(value: u64) -> Self {
+        Self { value, iec: true }
+    }
+}
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.iec {
+            write_scaled(f, self.value, 1024, IEC_BYTE_UNITS, " ")
+        } else {
+            write_scaled(f, self.value, 1000, SI_BYTE_UNITS, " ")
+        }
+    }
+}
+
+/// A compact count, such as `999`, `12.3k` or `1.2M`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Count(pub u64);
+
+impl fmt::Display for Count {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_scaled(f, self.0, 1000, COUNT_UNITS, "")
+    }
+}
+
+/// A percentage with a fixed number of decimal places, such as `98.5%`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Percent {
+    value: f32,
+    precision: u8,
+}
+
+impl Percent {
+    /// The maximum number of decimal places supported.
+    pub const MAX_PRECISION: u8 = 6;
+
+    /// A percentage with no decimal places.
+    pub const fn new(value: f32) -> Self {
+        Self::with_precision(value, 0)
+    }
+
+    /// A percentage with a given number of decimal places.
+    ///
+    /// The precision is capped at [`Percent::MAX_PRECISION`].
+    pub const fn with_precision(value: f32, precision: u8) -> Self {
+        let precision = if precision > Self::MAX_PRECISION {
+            Self::MAX_PRECISION
+        } else {
+            precision
+        };
+        Self { value, precision }
+    }
+}
+
+impl fmt::Display for Percent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scale = 10u64.pow(self.precision as u32);
+        let scaled = self.value as f64 * scale as f64;
+        if scaled.is_nan() {
+            return f.write_str("NaN%");
+        }
+        // Round half away from zero, saturating on overflow.
+        let rounded = (scaled.abs() + 0.5) as u64;
+        if scaled < 0.0 && rounded > 0 {
+            f.write_char('-')?;
+        }
+        itoa::fmt(&mut *f, rounded / scale)?;
+        if self.precision > 0 {
+            f.write_char('.')?;
+            let frac = rounded % scale;
+            let mut digit_scale = scale / 10;
+            while digit_scale > 0 {
+                f.write_char((b'0' + ((frac / digit_scale) % 10) as u8) as char)?;
+                digit_scale /= 10;
+            }
+        }
+        f.write_char('%')
+    }
+}
+
+/// A duration using its two most significant units, such as `3m 12s`.
+///
+/// Durations under a second are written in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Duration(pub StdDuration);
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.0.as_secs();
+        if secs == 0 {
+            itoa::fmt(&mut *f, self.0.subsec_millis())?;
+            return f.write_str("ms");
+        }
+        let parts = [
+            (secs / 86400, "d"),
+            (secs / 3600 % 24, "h"),
+            (secs / 60 % 60, "m"),
+            (secs % 60, "s"),
+        ];
+        let first = parts.iter().position(|(v, _)| *v > 0).unwrap_or(3);
+        let (value, unit) = parts[first];
+        itoa::fmt(&mut *f, value)?;
+        f.write_str(unit)?;
+        if let Some(&(value, unit)) = parts.get(first + 1) {
+            if value > 0 {
+                f.write_char(' ')?;
+                itoa::fmt(&mut *f, value)?;
+                f.write_str(unit)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<StdDuration> for Duration {
+    fn from(duration: StdDuration) -> Self {
+        Self(duration)
+    }
+}
+
+/// A normalized version string, such as `v1.2.3`.
+///
+/// Leading `v`, `V` or `=` characters and surrounding whitespace are removed,
+/// and versions starting with a digit are prefixed with `v`. Other values,
+/// such as `latest`, are written as is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Version<'a>(pub &'a str);
+
+impl<'a> fmt::Display for Version<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let trimmed = self.0.trim();
+        let version = trimmed.trim_start_matches(['v', 'V', '=']);
+        if version.starts_with(|c: char| c.is_ascii_digit()) {
+            f.write_char('v')?;
+            f.write_str(version)
+        } else {
+            f.write_str(trimmed)
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Writes a value scaled to the largest unit it meets, with one decimal place
+/// while the whole part is under 100.
+fn write_scaled(
+    f: &mut fmt::Formatter<'_>,
+    value: u64,
+    base: u64,
+    units: &[&str],
+    separator: &str,
+) -> fmt::Result {
+    let value = value as u128;
+    let base = base as u128;
+    let mut exp = 0;
+    let mut div = 1u128;
+    while exp + 1 < units.len() && value >= div * base {
+        div *= base;
+        exp += 1;
+    }
+    if exp > 0 {
+        // Rounding may carry into the next unit, eg. 999.95k -> 1M.
+        if (value * 10 + div / 2) / div >= base * 10 && exp + 1 < units.len() {
+            div *= base;
+            exp += 1;
+        }
+        let tenths = (value * 10 + div / 2) / div;
+        if tenths < 1000 {
+            itoa::fmt(&mut *f, (tenths / 10) as u64)?;
+            if !tenths.is_multiple_of(10) {
+                f.write_char('.')?;
+                f.write_char((b'0' + (tenths % 10) as u8) as char)?;
+            }
+        } else {
+            itoa::fmt(&mut *f, ((value + div / 2) / div) as u64)?;
+        }
+    } else {
+        itoa::fmt(&mut *f, value as u64)?;
+    }
+    f.write_str(separator)?;
+    f.write_str(units[exp])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(Bytes::si(0).to_string(), "0 B");
+        assert_eq!(Bytes::si(999).to_string(), "999 B");
+        assert_eq!(Bytes::si(1000).to_string(), "1 KB");
+        assert_eq!(Bytes::si(4200).to_string(), "4.2 KB");
+        assert_eq!(Bytes::si(123_456).to_string(), "123 KB");
+        assert_eq!(Bytes::si(999_960).to_string(), "1 MB");
+        assert_eq!(Bytes::si(u64::MAX).to_string(), "18.4 EB");
+        assert_eq!(Bytes::iec(1023).to_string(), "1023 B");
+        assert_eq!(Bytes::iec(1024).to_string(), "1 KiB");
+        assert_eq!(Bytes::iec(4300).to_string(), "4.2 KiB");
+        assert_eq!(Bytes::iec(3 * 1024 * 1024).to_string(), "3 MiB");
+    }
+
+    #[test]
+    fn test_count() {
+        assert_eq!(Count(0).to_string(), "0");
+        assert_eq!(Count(999).to_string(), "999");
+        assert_eq!(Count(12_345).to_string(), "12.3k");
+        assert_eq!(Count(123_456).to_string(), "123k");
+        assert_eq!(Count(999_999).to_string(), "1M");
+        assert_eq!(Count(1_234_567).to_string(), "1.2M");
+    }
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(Percent::new(98.6).to_string(), "99%");
+        assert_eq!(Percent::with_precision(98.64, 1).to_string(), "98.6%");
+        assert_eq!(Percent::with_precision(5.0, 2).to_string(), "5.00%");
+        assert_eq!(Percent::with_precision(0.05, 2).to_string(), "0.05%");
+        assert_eq!(Percent::with_precision(-1.25, 1).to_string(), "-1.3%");
+        assert_eq!(Percent::with_precision(-0.01, 1).to_string(), "0.0%");
+    }
+
+    #[test]
+    fn test_duration() {
+        let d = |secs, millis: u32| Duration(StdDuration::new(secs, millis * 1_000_000));
+        assert_eq!(d(0, 0).to_string(), "0ms");
+        assert_eq!(d(0, 250).to_string(), "250ms");
+        assert_eq!(d(12, 500).to_string(), "12s");
+        assert_eq!(d(192, 0).to_string(), "3m 12s");
+        assert_eq!(d(3600, 0).to_string(), "1h");
+        assert_eq!(d(3725, 0).to_string(), "1h 2m");
+        assert_eq!(d(90000, 0).to_string(), "1d 1h");
+    }
+
+    #[test]
+    fn test_version() {
+        assert_eq!(Version("1.2.3").to_string(), "v1.2.3");
+        assert_eq!(Version("v1.2.3").to_string(), "v1.2.3");
+        assert_eq!(Version(" V1.2.3-beta.1 ").to_string(), "v1.2.3-beta.1");
+        assert_eq!(Version("=0.1").to_string(), "v0.1");
+        assert_eq!(Version("latest").to_string(), "latest");
+    }
+}
@@ -53,19 +53,51 @@
 )]
 
 extern crate alloc;
-
+#[cfg(feature = "system-fonts")]
+extern crate std;
+
+#[cfg(feature = "serde")]
+pub mod endpoint;
+pub mod format;
+#[cfg(feature = "serde")]
+pub mod manifest;
+pub mod presets;
+pub mod sprite;
+pub mod url;
+
+mod ansi;
 mod font;
+#[cfg(feature = "png")]
+mod png;
+#[cfg(feature = "serde")]
+mod serde_impl;
 mod style;
 mod svg;
+#[cfg(feature = "system-fonts")]
+mod system;
 mod util;
+#[cfg(feature = "woff")]
+mod woff;
+#[cfg(feature = "woff")]
+mod woff2;
 
 use alloc::string::String;
-use core::{fmt, str};
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::str;
 
+pub use self::ansi::*;
 pub use self::font::*;
+#[cfg(feature = "png")]
+pub use self::png::*;
 pub use self::style::*;
+#[cfg(feature = "system-fonts")]
+pub use self::system::*;
+#[cfg(feature = "woff")]
+pub use self::woff::*;
 
 use self::svg::SvgWrite;
+use self::util::{write_number, Escape, Fnv1a};
 
 const MASK_ID: &str = "m";
 const GRADIENT_ID: &str = "g";
@@ -98,15 +130,132 @@ pub fn font<'a>(font: &'a ttf_parser::Face<'a>) -> CachedFont<TrueTypeFont<'a>>
 
 /// Prepares a cached True Type Font for use in generating badges with a given
 /// precision.
+///
+/// The precision is the number of decimal places of SVG path coordinates, up
+/// to a maximum of 4. Coordinates are in viewbox units, of which there are 100
+/// per pixel, so a precision above 0 is rarely visible.
 pub fn font_with_precision<'a>(
     font: &'a ttf_parser::Face<'a>,
     precision: u8,
 ) -> CachedFont<TrueTypeFont<'a>> {
-    CachedFont::new(TrueTypeFont::new(font, LINE_HEIGHT as f32, precision))
+    font_with_encoding(font, precision, PathEncoding::Standard)
+}
+
+/// Prepares a cached True Type Font for use in generating badges with a given
+/// precision and path encoding.
+///
+/// Use [`PathEncoding::Minimal`] for the smallest badges.
+pub fn font_with_encoding<'a>(
+    font: &'a ttf_parser::Face<'a>,
+    precision: u8,
+    encoding: PathEncoding,
+) -> CachedFont<TrueTypeFont<'a>> {
+    CachedFont::new(TrueTypeFont::with_encoding(
+        font,
+        LINE_HEIGHT as f32,
+        precision,
+        encoding,
+    ))
+}
+
+/// Prepares a cached True Type Font from font data for use in generating badges
+/// with integer SVG paths, given the index of the face within a collection.
+///
+/// See [`parse_font`] for the supported formats.
+///
+/// # Example
+///
+/// ```rust
+/// let data = include_bytes!("../data/fonts/notosans/NotoSans-Regular.ttf");
+/// let mut font = badgen::font_from_slice(data, 0).unwrap();
+/// let mut scratch = String::new();
+/// let mut out = String::new();
+///
+/// badgen::write_badge_with_font(
+///     &mut out,
+///     &badgen::Style::classic(),
+///     "passing",
+///     Some("build"),
+///     &mut font,
+///     &mut scratch,
+/// )
+/// .unwrap();
+/// ```
+pub fn font_from_slice(data: &[u8], index: u32) -> Result<CachedFont<TrueTypeFont<'_>>, FontError> {
+    TrueTypeFont::from_slice(data, index, LINE_HEIGHT as f32, 0).map(CachedFont::new)
+}
+
+/// Prepares a cached True Type Font for use in generating badges with the
+/// font weight and style of a badge style.
+///
+/// Weights and styles the face does not have are synthesized, see
+/// [`TrueTypeFont::synthesize`].
+pub fn font_for_style<'a>(
+    font: &ttf_parser::Face<'a>,
+    style: &Style<'_>,
+) -> CachedFont<TrueTypeFont<'a>> {
+    CachedFont::new(
+        TrueTypeFont::new(font, LINE_HEIGHT as f32, 0)
+            .synthesize(style.font_weight, style.font_style),
+    )
+}
+
+/// Cached fonts for the font family, weight and style of badge styles, for
+/// generating badges of many styles.
+///
+/// Fonts are prepared with [`style_font`] and [`font_for_style`] the first
+/// time a style needs them.
+///
+/// # Example
+///
+/// ```rust
+/// let mut fonts = badgen::StyleFonts::new();
+/// let mut scratch = String::new();
+///
+/// for style in [badgen::Style::classic(), badgen::Style::flat()].iter() {
+///     let mut badge = String::new();
+///     let font = fonts.get(style);
+///     badgen::write_badge_with_font(&mut badge, style, "passing", None, font, &mut scratch)
+///         .unwrap();
+///     println!("{}", badge);
+/// }
+/// ```
+#[cfg(any(feature = "font-noto-sans", feature = "font-dejavu-sans"))]
+#[derive(Debug, Default)]
+pub struct StyleFonts {
+    fonts: Vec<(FontKey, CachedFont<TrueTypeFont<'static>>)>,
+}
+
+#[cfg(any(feature = "font-noto-sans", feature = "font-dejavu-sans"))]
+type FontKey = (FontFamily, FontWeight, FontStyle);
+
+#[cfg(any(feature = "font-noto-sans", feature = "font-dejavu-sans"))]
+impl StyleFonts {
+    /// Construct a new [`StyleFonts`] without any fonts prepared.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the font for a badge style, preparing it on first use.
+    pub fn get(&mut self, style: &Style<'_>) -> &mut CachedFont<TrueTypeFont<'static>> {
+        let key = (style.font_family, style.font_weight, style.font_style);
+        let index = match self.fonts.iter().position(|(k, _)| *k == key) {
+            Some(index) => index,
+            None => {
+                let font = font_for_style(&style_font(style), style);
+                self.fonts.push((key, font));
+                self.fonts.len() - 1
+            }
+        };
+        &mut self.fonts[index].1
+    }
 }
 
 /// Generate an SVG badge given a style, status and optional label.
 ///
+/// The status may be any [`fmt::Display`], such as the formatters found in
+/// [`format`].
+///
 /// Uses the default font provided by this library.
 ///
 /// # Example
@@ -120,8 +269,11 @@ pub fn font_with_precision<'a>(
 ///
 /// println!("{}", badge);
 /// ```
-#[cfg(feature = "font-noto-sans")]
-pub fn badge(style: &Style<'_>, status: &str, label: Option<&str>) -> Result<String, fmt::Error> {
+#[cfg(any(feature = "font-noto-sans", feature = "font-dejavu-sans"))]
+pub fn badge<S>(style: &Style<'_>, status: S, label: Option<&str>) -> Result<String, fmt::Error>
+where
+    S: fmt::Display,
+{
     let mut out = String::with_capacity(8192);
     write_badge(&mut out, style, status, label)?;
     Ok(out)
@@ -131,18 +283,19 @@ pub fn badge(style: &Style<'_>, status: &str, label: Option<&str>) -> Result<Str
 /// label.
 ///
 /// Uses the default font provided by this library.
-#[cfg(feature = "font-noto-sans")]
-pub fn write_badge<W>(
+#[cfg(any(feature = "font-noto-sans", feature = "font-dejavu-sans"))]
+pub fn write_badge<W, S>(
     w: &mut W,
     style: &Style<'_>,
-    status: &str,
+    status: S,
     label: Option<&str>,
 ) -> Result<(), fmt::Error>
 where
     W: fmt::Write,
+    S: fmt::Display,
 {
-    let ttf_font = notosans_font();
-    let mut font = font(&ttf_font);
+    let ttf_font = style_font(style);
+    let mut font = font_for_style(&ttf_font, style);
     let mut scratch = String::with_capacity(4096);
     write_badge_with_font(w, style, status, label, &mut font, &mut scratch)
 }
@@ -151,104 +304,345 @@ where
 /// label, font and scratch space.
 ///
 /// The scratch space is used for minimal to zero allocations with repeated use.
+/// It is unused when text is rendered as glyphs, see [`TextRendering`].
 ///
-/// Prepare fonts for this function with `font` or `font_with_precision`.
-pub fn write_badge_with_font<W, F>(
+/// Prepare fonts for this function with `font`, `font_with_precision` or
+/// `font_with_encoding`.
+pub fn write_badge_with_font<W, S, F>(
     w: &mut W,
     style: &Style<'_>,
-    status: &str,
+    status: S,
     label: Option<&str>,
     font: &mut F,
     scratch: &mut String,
 ) -> Result<(), fmt::Error>
 where
     W: fmt::Write,
+    S: fmt::Display,
+    F: Font,
+{
+    write_badge_with_text(w, style, status, label, font, scratch)
+}
+
+/// Writes an SVG badge given any displayable status and label text.
+pub(crate) fn write_badge_with_text<W, S, L, F>(
+    w: &mut W,
+    style: &Style<'_>,
+    status: S,
+    label: Option<L>,
+    font: &mut F,
+    scratch: &mut String,
+) -> Result<(), fmt::Error>
+where
+    W: fmt::Write,
+    S: fmt::Display,
+    L: fmt::Display,
+    F: Font,
+{
+    match style.text_rendering {
+        TextRendering::Paths => write_badge_with_paths(w, style, status, label, font, scratch),
+        TextRendering::Glyphs => write_badge_with_glyphs(w, style, status, label, font),
+    }
+}
+
+/// Writes an SVG badge with a single path per text.
+fn write_badge_with_paths<W, S, L, F>(
+    w: &mut W,
+    style: &Style<'_>,
+    status: S,
+    label: Option<L>,
+    font: &mut F,
+    scratch: &mut String,
+) -> Result<(), fmt::Error>
+where
+    W: fmt::Write,
+    S: fmt::Display,
+    L: fmt::Display,
     F: Font,
 {
     // Clear the scratch buffer from any previous run.
     scratch.clear();
 
-    let viewbox_scale = VIEWBOX_HEIGHT as f32 / style.height as f32;
-    let line_margin = (VIEWBOX_HEIGHT - font.height()) / 2;
-    let text_spacing = style.text_spacing as f32 * VIEWBOX_USER_SCALE as f32;
-
-    let mut status_path_offset = 0;
-    let mut next_text_origin = Point {
-        x: SIDE_MARGIN,
-        y: VIEWBOX_HEIGHT - line_margin,
-    };
+    let text_spacing = text_spacing(style);
+    let mut next_text_origin = text_origin(font);
 
     // If a label is specified, render and calculate the width.
-    let label_width = if let Some(label) = label {
-        let label_width = render_text_path(font, next_text_origin, label, text_spacing, scratch);
-        status_path_offset += scratch.len();
-        next_text_origin.x += label_width + MIDDLE_MARGIN;
-        label_width
+    let label_width = if let Some(ref label) = label {
+        let label_width = render_text_path(font, next_text_origin, label, text_spacing, scratch)?;
+        next_text_origin.x += label_width + MIDDLE_MARGIN + separator_spacing(style);
+        Some(label_width)
     } else {
-        0
+        None
     };
 
+    let status_path_offset = scratch.len();
     let has_label = status_path_offset > 0;
 
     // Render the status text path into the scratch buffer.
-    let status_width = render_text_path(font, next_text_origin, status, text_spacing, scratch);
+    let status_width = render_text_path(font, next_text_origin, &status, text_spacing, scratch)?;
 
-    // Calculate rect widths.
-    let (status_rect_width, label_rect_width) = if has_label {
-        let rect_margin = SIDE_MARGIN + (MIDDLE_MARGIN / 2);
-        (status_width + rect_margin, label_width + rect_margin)
+    let layout = Layout::new(style, label_width.filter(|_| has_label), status_width);
+
+    let text = if has_label {
+        let (label, status) = scratch.split_at(status_path_offset);
+        BadgeText::Paths {
+            label: Some(label),
+            status,
+        }
     } else {
-        let rect_margin = SIDE_MARGIN * 2;
-        (status_width + rect_margin, 0)
+        BadgeText::Paths {
+            label: None,
+            status: &scratch[..],
+        }
     };
 
-    // Calculate the viewbox size.
-    let viewbox_size = Point {
-        x: status_rect_width + label_rect_width,
-        y: VIEWBOX_HEIGHT,
-    };
+    let prefix = id_prefix(style, |hasher| {
+        write!(
+            hasher,
+            "{:?}{:?}{}{}",
+            style, label_width, status_width, scratch
+        )
+    })?;
+
+    let label = label.as_ref().map(|l| l as &dyn fmt::Display);
+    write_badge_svg(w, style, &layout, &text, prefix, label, &status)
+}
+
+/// Writes an SVG badge with each unique glyph defined once.
+fn write_badge_with_glyphs<W, S, L, F>(
+    w: &mut W,
+    style: &Style<'_>,
+    status: S,
+    label: Option<L>,
+    font: &mut F,
+) -> Result<(), fmt::Error>
+where
+    W: fmt::Write,
+    S: fmt::Display,
+    L: fmt::Display,
+    F: Font,
+{
+    let mut glyphs = GlyphSet::default();
+    let mut label_uses = Vec::new();
+    let mut status_uses = Vec::new();
+
+    let (label_width, status_width) = render_badge_glyphs(
+        style,
+        &status,
+        label.as_ref(),
+        font,
+        &mut glyphs,
+        &mut label_uses,
+        &mut status_uses,
+    )?;
+    let layout = Layout::new(style, label_width, status_width);
 
-    // Calculate the image size.
-    let image_size = Point {
-        x: (viewbox_size.x as f32 / viewbox_scale) as u32,
-        y: (viewbox_size.y as f32 / viewbox_scale) as u32,
+    let prefix = id_prefix(style, |hasher| {
+        write!(hasher, "{:?}{:?}{}", style, label_width, status_width)?;
+        glyphs.paths().try_for_each(|path| hasher.write_str(path))?;
+        write!(hasher, "{:?}{:?}", label_uses, status_uses)
+    })?;
+
+    let text = BadgeText::Glyphs {
+        label: Some(&label_uses[..]).filter(|_| label_width.is_some()),
+        status: &status_uses,
+        paths: Some(&glyphs),
+        glyph_prefix: prefix,
     };
 
-    let (label_text_path, status_text_path) = if has_label {
-        let (label, status) = scratch.split_at(status_path_offset);
-        (Some(label), status)
+    let label = label.as_ref().map(|l| l as &dyn fmt::Display);
+    write_badge_svg(w, style, &layout, &text, prefix, label, &status)
+}
+
+/// Returns the element ID prefix of a style, given a function writing the
+/// badge content to hash.
+fn id_prefix<'a, H>(style: &Style<'a>, hash: H) -> Result<Prefix<'a>, fmt::Error>
+where
+    H: FnOnce(&mut Fnv1a) -> fmt::Result,
+{
+    Ok(match style.id_prefix {
+        IdPrefix::None => Prefix::None,
+        IdPrefix::Custom(prefix) => Prefix::Custom(prefix.as_str()),
+        IdPrefix::Hash => {
+            let mut hasher = Fnv1a::new();
+            hash(&mut hasher)?;
+            Prefix::Hash(hasher.finish())
+        }
+    })
+}
+
+/// Renders the label and status of a badge as glyph placements, returning
+/// the label and status widths.
+///
+/// The label width is `None` if there is no label to display.
+pub(crate) fn render_badge_glyphs<S, L, F>(
+    style: &Style<'_>,
+    status: S,
+    label: Option<L>,
+    font: &mut F,
+    glyphs: &mut GlyphSet,
+    label_uses: &mut Vec<GlyphUse>,
+    status_uses: &mut Vec<GlyphUse>,
+) -> Result<(Option<u32>, u32), fmt::Error>
+where
+    S: fmt::Display,
+    L: fmt::Display,
+    F: Font,
+{
+    label_uses.clear();
+    status_uses.clear();
+
+    let text_spacing = text_spacing(style);
+    let mut next_text_origin = text_origin(font);
+
+    let label_width = if let Some(label) = label {
+        let label_width = render_text_glyphs(
+            font,
+            next_text_origin,
+            label,
+            text_spacing,
+            glyphs,
+            label_uses,
+        )?;
+        next_text_origin.x += label_width + MIDDLE_MARGIN + separator_spacing(style);
+        Some(label_width)
     } else {
-        (None, &scratch[..])
+        None
     };
 
-    ///////////////////////////////////////////////////////////////////////////
+    let status_width = render_text_glyphs(
+        font,
+        next_text_origin,
+        status,
+        text_spacing,
+        glyphs,
+        status_uses,
+    )?;
 
+    Ok((label_width.filter(|_| !label_uses.is_empty()), status_width))
+}
+
+/// The rendered text of a badge.
+#[derive(Debug)]
+enum BadgeText<'a> {
+    /// A single path per text.
+    Paths {
+        label: Option<&'a str>,
+        status: &'a str,
+    },
+    /// Placements of glyphs, with the glyph paths to define within the badge
+    /// if they are not defined elsewhere in the document.
+    Glyphs {
+        label: Option<&'a [GlyphUse]>,
+        status: &'a [GlyphUse],
+        paths: Option<&'a GlyphSet>,
+        glyph_prefix: Prefix<'a>,
+    },
+}
+
+impl<'a> BadgeText<'a> {
+    fn has_label(&self) -> bool {
+        match self {
+            Self::Paths { label, .. } => label.is_some(),
+            Self::Glyphs { label, .. } => label.is_some(),
+        }
+    }
+}
+
+/// Writes a standalone SVG badge.
+fn write_badge_svg<W>(
+    w: &mut W,
+    style: &Style<'_>,
+    layout: &Layout,
+    text: &BadgeText<'_>,
+    prefix: Prefix<'_>,
+    label: Option<&dyn fmt::Display>,
+    status: &dyn fmt::Display,
+) -> fmt::Result
+where
+    W: fmt::Write,
+{
     let mut svg = SvgWrite::start(w)?;
+    write_size(&mut svg, layout)?;
+    svg.attr_str("xmlns", "http://www.w3.org/2000/svg")?;
+
+    write_badge_body(&mut svg, style, layout, text, prefix, label, status)?;
+
+    svg.finish().map(drop)
+}
 
-    svg.attr_int("width", image_size.x)?
-        .attr_int("height", image_size.y)?
+/// Writes the size and viewbox attributes of a badge.
+fn write_size<W>(svg: &mut SvgWrite<W>, layout: &Layout) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    svg.attr_int("width", layout.image_size.x)?
+        .attr_int("height", layout.image_size.y)?
         .attr_fn("viewBox", |mut w| {
             w.write_str("0 0 ")?;
-            write_int(&mut w, viewbox_size.x)?;
+            write_int(&mut w, layout.viewbox_size.x)?;
             w.write_char(' ')?;
-            write_int(&mut w, viewbox_size.y)
-        })?
-        .attr_str("xmlns", "http://www.w3.org/2000/svg")?;
+            write_int(&mut w, layout.viewbox_size.y)
+        })?;
+    Ok(())
+}
+
+/// Writes the content of a badge within an open `svg` or `symbol` element.
+fn write_badge_body<W>(
+    svg: &mut SvgWrite<W>,
+    style: &Style<'_>,
+    layout: &Layout,
+    text: &BadgeText<'_>,
+    prefix: Prefix<'_>,
+    label: Option<&dyn fmt::Display>,
+    status: &dyn fmt::Display,
+) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let &Layout {
+        label_rect_width,
+        status_rect_width,
+        viewbox_size,
+        ..
+    } = layout;
+    let has_label = text.has_label();
+    let id = |name| Id { prefix, name };
 
     ///////////////////////////////////////////////////////////////////////////
 
     svg.open("defs")?;
 
-    svg.open("path")?
-        .attr_str("id", STATUS_PATH_ID)?
-        .attr_str("d", status_text_path)?
-        .close_inline()?;
-
-    if let Some(label_text_path) = label_text_path {
-        svg.open("path")?
-            .attr_str("id", LABEL_PATH_ID)?
-            .attr_str("d", label_text_path)?
-            .close_inline()?;
+    match *text {
+        BadgeText::Paths { label, status } => {
+            svg.open("path")?
+                .attr_fn("id", |w| write_id(w, id(STATUS_PATH_ID)))?
+                .attr_str("d", status)?
+                .close_inline()?;
+
+            if let Some(label) = label {
+                svg.open("path")?
+                    .attr_fn("id", |w| write_id(w, id(LABEL_PATH_ID)))?
+                    .attr_str("d", label)?
+                    .close_inline()?;
+            }
+        }
+        BadgeText::Glyphs {
+            label,
+            status,
+            paths,
+            glyph_prefix,
+        } => {
+            if let Some(paths) = paths {
+                write_glyph_paths(svg, paths, glyph_prefix)?;
+            }
+
+            write_glyph_group(svg, status, id(STATUS_PATH_ID), glyph_prefix)?;
+
+            if let Some(label) = label {
+                write_glyph_group(svg, label, id(LABEL_PATH_ID), glyph_prefix)?;
+            }
+        }
     }
 
     svg.close("defs")?;
@@ -256,33 +650,24 @@ where
     ///////////////////////////////////////////////////////////////////////////
 
     let requires_mask = if let Some(ref gradient) = style.gradient {
-        svg.open("linearGradient")?
-            .attr_str("id", GRADIENT_ID)?
-            .attr_str("x2", "0")?
-            .attr_str("y2", "100%")?
-            .open("stop")?
-            .attr_str("offset", "0")?
-            .attr_fn("stop-opacity", |w| write_opacity(w, gradient.opacity))?
-            .attr_fn("stop-color", |w| write_color(w, gradient.start))?
-            .close_inline()?
-            .open("stop")?
-            .attr_str("offset", "1")?
-            .attr_fn("stop-opacity", |w| write_opacity(w, gradient.opacity))?;
-
-        if let Some(end) = gradient.end {
-            svg.attr_fn("stop-color", |w| write_color(w, end))?;
-        }
-
-        svg.close_inline()?.close("linearGradient")?;
+        write_gradient(svg, gradient, id(GRADIENT_ID))?;
         true
     } else {
         style.border_radius > 0
     };
 
+    // A gap separator is masked out of the background.
+    let gap_width = match style.separator {
+        Some(Separator::Gap { width }) if has_label => Some(width as u32 * VIEWBOX_USER_SCALE),
+        _ => None,
+    };
+    let requires_mask = requires_mask || gap_width.is_some();
+
     ///////////////////////////////////////////////////////////////////////////
 
     if requires_mask {
-        svg.open("mask")?.attr_str("id", MASK_ID)?;
+        svg.open("mask")?
+            .attr_fn("id", |w| write_id(w, id(MASK_ID)))?;
 
         svg.open("rect")?
             .attr_int("width", viewbox_size.x)?
@@ -293,31 +678,38 @@ where
             svg.attr_int("rx", style.border_radius as u32 * VIEWBOX_USER_SCALE)?;
         }
 
-        svg.close_inline()?
-            .close("mask")?
+        svg.close_inline()?;
+
+        if let Some(gap_width) = gap_width {
+            write_rect_path(
+                svg,
+                Point {
+                    x: label_rect_width,
+                    y: 0,
+                },
+                Point {
+                    x: gap_width,
+                    y: VIEWBOX_HEIGHT,
+                },
+                Fill::Color(Color::Custom("000")),
+            )?;
+        }
+
+        svg.close("mask")?
             .open("g")?
-            .attr_fn("mask", |w| write_id_url(w, MASK_ID))?;
+            .attr_fn("mask", |w| write_id_url(w, id(MASK_ID)))?;
     }
 
     ///////////////////////////////////////////////////////////////////////////
 
-    if has_label {
-        write_rect_path(
-            &mut svg,
-            VIEWBOX_ORIGIN,
-            Point {
-                x: label_rect_width,
-                y: VIEWBOX_HEIGHT,
-            },
-            style
-                .label_background
-                .map(Fill::Color)
-                .unwrap_or(Fill::None),
-        )?;
-    }
-
-    write_rect_path(
-        &mut svg,
+    let label_rect = (
+        VIEWBOX_ORIGIN,
+        Point {
+            x: label_rect_width,
+            y: VIEWBOX_HEIGHT,
+        },
+    );
+    let status_rect = (
         Point {
             x: label_rect_width,
             y: 0,
@@ -326,31 +718,103 @@ where
             x: status_rect_width,
             y: VIEWBOX_HEIGHT,
         },
-        Fill::Color(style.background),
-    )?;
+    );
+
+    let colors = Colors::new(style);
+
+    if has_label {
+        match colors.label_background {
+            Some(label_background) => {
+                write_rect_path(
+                    svg,
+                    label_rect.0,
+                    label_rect.1,
+                    Fill::Color(label_background),
+                )?;
+            }
+            // Without a label background, the label path is left unfilled
+            // and so drawn in the SVG default of black. Ghost badges leave
+            // it out for a transparent label.
+            None if !style.ghost => {
+                write_rect_path(svg, label_rect.0, label_rect.1, Fill::None)?;
+            }
+            None => {}
+        }
+    }
 
-    if style.gradient.is_some() {
+    if let Some(status_background) = colors.status_background {
         write_rect_path(
-            &mut svg,
-            VIEWBOX_ORIGIN,
-            viewbox_size,
-            Fill::Id(GRADIENT_ID),
+            svg,
+            status_rect.0,
+            status_rect.1,
+            Fill::Color(status_background),
         )?;
     }
 
+    if has_label {
+        match style.separator {
+            Some(Separator::Solid { color, width }) => {
+                let width = width as u32 * VIEWBOX_USER_SCALE;
+                write_rect_path(
+                    svg,
+                    Point {
+                        x: label_rect_width.saturating_sub(width / 2),
+                        y: 0,
+                    },
+                    Point {
+                        x: width,
+                        y: VIEWBOX_HEIGHT,
+                    },
+                    Fill::Color(color),
+                )?;
+            }
+            Some(Separator::Chevron { width }) => {
+                if let Some(label_background) = colors.label_background {
+                    write_chevron_path(
+                        svg,
+                        label_rect_width,
+                        width as u32 * VIEWBOX_USER_SCALE,
+                        label_background,
+                    )?;
+                }
+            }
+            Some(Separator::Gap { .. }) | None => {}
+        }
+    }
+
+    if let Some(ref gradient) = style.gradient {
+        let (badge, label, status) = match gradient.target {
+            GradientTarget::Badge => (true, false, false),
+            GradientTarget::Segments => (false, has_label, true),
+            GradientTarget::Label => (false, has_label, false),
+            GradientTarget::Status => (false, false, true),
+        };
+        let rects = [
+            (badge, (VIEWBOX_ORIGIN, viewbox_size)),
+            (label, label_rect),
+            (status, status_rect),
+        ];
+        for (_, (origin, size)) in rects.iter().filter(|(enabled, _)| *enabled) {
+            write_rect_path(svg, *origin, *size, Fill::Id(id(GRADIENT_ID)))?;
+        }
+    }
+
     if requires_mask {
         svg.close("g")?;
     }
 
+    if let Some(border) = colors.border {
+        write_border(svg, border, viewbox_size, style.border_radius)?;
+    }
+
     ///////////////////////////////////////////////////////////////////////////
 
     if let Some(label) = label {
-        let text_color = style.label_text_color.unwrap_or(style.text_color);
         write_text_path_ref(
-            &mut svg,
+            svg,
             label,
-            text_color,
-            LABEL_PATH_ID,
+            colors.label_text,
+            id(LABEL_PATH_ID),
             style.text_shadow_color,
             style.text_shadow_opacity,
             style.text_shadow_offset,
@@ -358,10 +822,10 @@ where
     }
 
     write_text_path_ref(
-        &mut svg,
+        svg,
         status,
-        style.text_color,
-        STATUS_PATH_ID,
+        colors.status_text,
+        id(STATUS_PATH_ID),
         style.text_shadow_color,
         style.text_shadow_opacity,
         style.text_shadow_offset,
@@ -369,14 +833,179 @@ where
 
     ///////////////////////////////////////////////////////////////////////////
 
-    svg.finish().map(drop)
+    Ok(())
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The geometry of a badge in viewbox units.
+pub(crate) struct Layout {
+    pub(crate) label_rect_width: u32,
+    pub(crate) status_rect_width: u32,
+    pub(crate) viewbox_size: Point,
+    pub(crate) image_size: Point,
+}
+
+impl Layout {
+    /// Calculates the badge geometry given the rendered text widths.
+    ///
+    /// The label width should be `None` if there is no label to display.
+    pub(crate) fn new(style: &Style<'_>, label_width: Option<u32>, status_width: u32) -> Self {
+        let viewbox_scale = VIEWBOX_HEIGHT as f32 / style.height as f32;
+
+        // Calculate rect widths.
+        let (status_rect_width, label_rect_width) = if let Some(label_width) = label_width {
+            let rect_margin = SIDE_MARGIN + (MIDDLE_MARGIN / 2);
+            (
+                status_width + rect_margin + separator_spacing(style),
+                label_width + rect_margin,
+            )
+        } else {
+            let rect_margin = SIDE_MARGIN * 2;
+            (status_width + rect_margin, 0)
+        };
+
+        // Calculate the viewbox size.
+        let viewbox_size = Point {
+            x: status_rect_width + label_rect_width,
+            y: VIEWBOX_HEIGHT,
+        };
+
+        // Calculate the image size.
+        let image_size = Point {
+            x: (viewbox_size.x as f32 / viewbox_scale) as u32,
+            y: (viewbox_size.y as f32 / viewbox_scale) as u32,
+        };
+
+        Self {
+            label_rect_width,
+            status_rect_width,
+            viewbox_size,
+            image_size,
+        }
+    }
+}
+
+/// The colors of a badge, with a ghost style applied.
+pub(crate) struct Colors<'a> {
+    pub(crate) label_background: Option<Color<'a>>,
+    pub(crate) status_background: Option<Color<'a>>,
+    pub(crate) label_text: Color<'a>,
+    pub(crate) status_text: Color<'a>,
+    pub(crate) border: Option<Border<'a>>,
+}
+
+impl<'a> Colors<'a> {
+    pub(crate) fn new(style: &Style<'a>) -> Self {
+        if style.ghost {
+            Self {
+                label_background: None,
+                status_background: None,
+                label_text: style
+                    .label_text_color
+                    .or(style.label_background)
+                    .unwrap_or(style.background),
+                status_text: style.background,
+                border: Some(Border {
+                    color: style.background,
+                    width: style.border.map_or(1, |b| b.width),
+                }),
+            }
+        } else {
+            Self {
+                label_background: style.label_background,
+                status_background: Some(style.background),
+                label_text: style.label_text_color.unwrap_or(style.text_color),
+                status_text: style.text_color,
+                border: style.border,
+            }
+        }
+    }
+}
+
+/// The origin of the first text in the badge.
+///
+/// Subsequent text is offset by the width of the previous text plus
+/// [`MIDDLE_MARGIN`].
+pub(crate) fn text_origin<F>(font: &F) -> Point
+where
+    F: Font,
+{
+    let line_margin = (VIEWBOX_HEIGHT - font.height()) / 2;
+    Point {
+        x: SIDE_MARGIN,
+        y: VIEWBOX_HEIGHT - line_margin,
+    }
+}
+
+/// The extra spacing between the label and status of a style in viewbox
+/// units.
+pub(crate) fn separator_spacing(style: &Style<'_>) -> u32 {
+    style
+        .separator
+        .map_or(0, |s| s.spacing() as u32 * VIEWBOX_USER_SCALE)
+}
+
+/// The letter spacing of a style in viewbox units.
+pub(crate) fn text_spacing(style: &Style<'_>) -> f32 {
+    style.text_spacing * VIEWBOX_USER_SCALE as f32
 }
 
 ///////////////////////////////////////////////////////////////////////////////
 
+/// The prefix of element IDs within an SVG badge.
+#[derive(Debug, Clone, Copy)]
+enum Prefix<'a> {
+    None,
+    Custom(&'a str),
+    Hash(u32),
+    /// The ID of a symbol in a sprite sheet, followed by a dash.
+    Symbol(&'a str),
+}
+
+impl<'a> fmt::Display for Prefix<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::None => Ok(()),
+            Self::Custom(prefix) => f.write_str(prefix),
+            Self::Hash(hash) => write!(f, "b{:08x}-", hash),
+            Self::Symbol(id) => {
+                Escape(id).fmt(&mut *f)?;
+                f.write_char('-')
+            }
+        }
+    }
+}
+
+/// An element ID within an SVG badge.
+#[derive(Clone, Copy)]
+struct Id<'a> {
+    prefix: Prefix<'a>,
+    name: &'static str,
+}
+
+impl<'a> fmt::Display for Id<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.prefix, self.name)
+    }
+}
+
+/// The element ID of a glyph path, given its index in a [`GlyphSet`].
+#[derive(Clone, Copy)]
+struct GlyphId<'a> {
+    prefix: Prefix<'a>,
+    index: usize,
+}
+
+impl<'a> fmt::Display for GlyphId<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}c{}", self.prefix, self.index)
+    }
+}
+
 enum Fill<'a> {
     None,
-    Id(&'a str),
+    Id(Id<'a>),
     Color(Color<'a>),
 }
 
@@ -385,9 +1014,9 @@ enum Fill<'a> {
 // TODO: text overlay / accessibility
 fn write_text_path_ref<W>(
     svg: &mut SvgWrite<W>,
-    _text: &str,
+    _text: &dyn fmt::Display,
     text_color: Color<'_>,
-    text_path_id: &str,
+    text_path_id: Id<'_>,
     text_shadow_color: Color<'_>,
     text_shadow_opacity: Opacity<'_>,
     text_shadow_offset: u16,
@@ -395,27 +1024,137 @@ fn write_text_path_ref<W>(
 where
     W: fmt::Write,
 {
-    svg.open("use")?
-        .attr_fn("href", |w| write_id(w, text_path_id))?
-        .attr_fn("fill", |w| write_color(w, text_shadow_color))?
-        .attr_fn("opacity", |w| write_opacity(w, text_shadow_opacity))?
-        .attr_fn("transform", |mut w| {
-            w.write_str("translate(")?;
-            write_int(&mut w, text_shadow_offset as u32 * VIEWBOX_USER_SCALE)?;
-            w.write_char(',')?;
-            write_int(&mut w, text_shadow_offset as u32 * VIEWBOX_USER_SCALE)?;
-            w.write_char(')')
-        })?
-        .close_inline()?;
+    if !text_shadow_opacity.is_transparent() {
+        svg.open("use")?
+            .attr_fn("href", |w| write_id_ref(w, text_path_id))?
+            .attr_fn("fill", |w| write_color(w, text_shadow_color))?
+            .attr_fn("opacity", |w| write_opacity(w, text_shadow_opacity))?
+            .attr_fn("transform", |mut w| {
+                w.write_str("translate(")?;
+                write_int(&mut w, text_shadow_offset as u32 * VIEWBOX_USER_SCALE)?;
+                w.write_char(',')?;
+                write_int(&mut w, text_shadow_offset as u32 * VIEWBOX_USER_SCALE)?;
+                w.write_char(')')
+            })?
+            .close_inline()?;
+    }
 
     svg.open("use")?
-        .attr_fn("href", |w| write_id(w, text_path_id))?
+        .attr_fn("href", |w| write_id_ref(w, text_path_id))?
         .attr_fn("fill", |w| write_color(w, text_color))?
         .close_inline()?;
 
     Ok(())
 }
 
+/// Writes a group of glyph placements for a text.
+///
+/// The group is translated to the baseline of the text, so glyphs on the
+/// baseline only need an `x` position.
+fn write_glyph_group<W>(
+    svg: &mut SvgWrite<W>,
+    uses: &[GlyphUse],
+    text_id: Id<'_>,
+    glyph_prefix: Prefix<'_>,
+) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let baseline = uses.first().map_or(0.0, |glyph_use| glyph_use.origin.y);
+
+    svg.open("g")?.attr_fn("id", |w| write_id(w, text_id))?;
+    if baseline != 0.0 {
+        svg.attr_fn("transform", |mut w| {
+            w.write_str("translate(0,")?;
+            write_number(&mut w, baseline)?;
+            w.write_char(')')
+        })?;
+    }
+
+    for glyph_use in uses {
+        let glyph_id = GlyphId {
+            prefix: glyph_prefix,
+            index: glyph_use.glyph,
+        };
+        svg.open("use")?
+            .attr_fn("href", |w| write!(w, "#{}", glyph_id))?
+            .attr_fn("x", |w| write_number(w, glyph_use.origin.x))?;
+        if glyph_use.origin.y != baseline {
+            svg.attr_fn("y", |w| write_number(w, glyph_use.origin.y - baseline))?;
+        }
+        svg.close_inline()?;
+    }
+
+    svg.close("g")?;
+    Ok(())
+}
+
+/// Writes the paths of a glyph set, to be referenced by glyph placements.
+fn write_glyph_paths<W>(
+    svg: &mut SvgWrite<W>,
+    glyphs: &GlyphSet,
+    glyph_prefix: Prefix<'_>,
+) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    for (index, path) in glyphs.paths().enumerate() {
+        let glyph_id = GlyphId {
+            prefix: glyph_prefix,
+            index,
+        };
+        svg.open("path")?
+            .attr_fn("id", |w| write!(w, "{}", glyph_id))?
+            .attr_str("d", path)?
+            .close_inline()?;
+    }
+    Ok(())
+}
+
+fn write_gradient<W>(svg: &mut SvgWrite<W>, gradient: &Gradient<'_>, id: Id<'_>) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let tag = match gradient.shape {
+        GradientShape::Linear(direction) => {
+            svg.open("linearGradient")?
+                .attr_fn("id", |w| write_id(w, id))?;
+            // Only write the points which differ from the SVG defaults.
+            let [start, end] = direction.points();
+            let points = [
+                ("x1", start.x, 0),
+                ("y1", start.y, 0),
+                ("x2", end.x, 100),
+                ("y2", end.y, 0),
+            ];
+            for (name, value, default) in points.iter() {
+                if value != default {
+                    svg.attr_fn(name, |w| write_percent(w, *value))?;
+                }
+            }
+            "linearGradient"
+        }
+        GradientShape::Radial => {
+            svg.open("radialGradient")?
+                .attr_fn("id", |w| write_id(w, id))?;
+            "radialGradient"
+        }
+    };
+
+    for stop in gradient.stops.iter() {
+        svg.open("stop")?
+            .attr_fn("offset", |w| write_number(w, stop.offset))?
+            .attr_fn("stop-opacity", |w| write_opacity(w, stop.opacity))?;
+        if let Some(color) = stop.color {
+            svg.attr_fn("stop-color", |w| write_color(w, color))?;
+        }
+        svg.close_inline()?;
+    }
+
+    svg.close(tag)?;
+    Ok(())
+}
+
 fn write_rect_path<W>(
     svg: &mut SvgWrite<W>,
     origin: Point,
@@ -452,6 +1191,58 @@ where
     Ok(())
 }
 
+fn write_chevron_path<W>(svg: &mut SvgWrite<W>, x: u32, width: u32, color: Color<'_>) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    svg.open("path")?
+        .attr_fn("d", |mut w| {
+            w.write_char('M')?;
+            write_int(&mut w, x)?;
+            w.write_str(" 0l")?;
+            write_int(&mut w, width)?;
+            w.write_char(' ')?;
+            write_int(&mut w, VIEWBOX_HEIGHT / 2)?;
+            w.write_str("l-")?;
+            write_int(&mut w, width)?;
+            w.write_char(' ')?;
+            write_int(&mut w, VIEWBOX_HEIGHT / 2)?;
+            w.write_char('z')
+        })?
+        .attr_fn("fill", |w| write_color(w, color))?
+        .close_inline()?;
+    Ok(())
+}
+
+fn write_border<W>(
+    svg: &mut SvgWrite<W>,
+    border: Border<'_>,
+    size: Point,
+    border_radius: u16,
+) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    // Inset the stroke so it is drawn entirely inside the badge.
+    let width = border.width as u32 * VIEWBOX_USER_SCALE;
+    svg.open("rect")?
+        .attr_int("x", width / 2)?
+        .attr_int("y", width / 2)?
+        .attr_int("width", size.x.saturating_sub(width))?
+        .attr_int("height", size.y.saturating_sub(width))?;
+
+    let radius = (border_radius as u32 * VIEWBOX_USER_SCALE).saturating_sub(width / 2);
+    if radius > 0 {
+        svg.attr_int("rx", radius)?;
+    }
+
+    svg.attr_str("fill", "none")?
+        .attr_fn("stroke", |w| write_color(w, border.color))?
+        .attr_int("stroke-width", width)?
+        .close_inline()?;
+    Ok(())
+}
+
 #[inline]
 fn write_int<W>(w: W, value: impl itoa::Integer) -> fmt::Result
 where
@@ -461,12 +1252,32 @@ where
 }
 
 #[inline]
-fn write_id<W>(mut w: W, id: &str) -> fmt::Result
+fn write_percent<W>(mut w: W, value: u8) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    write_int(&mut w, value)?;
+    if value != 0 {
+        w.write_char('%')?;
+    }
+    Ok(())
+}
+
+#[inline]
+fn write_id<W>(mut w: W, id: Id<'_>) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    write!(w, "{}", id)
+}
+
+#[inline]
+fn write_id_ref<W>(mut w: W, id: Id<'_>) -> fmt::Result
 where
     W: fmt::Write,
 {
     w.write_char('#')?;
-    w.write_str(id)
+    write_id(w, id)
 }
 
 #[inline]
@@ -486,11 +1297,242 @@ where
 }
 
 #[inline]
-fn write_id_url<W>(mut w: W, id: &str) -> fmt::Result
+fn write_id_url<W>(mut w: W, id: Id<'_>) -> fmt::Result
 where
     W: fmt::Write,
 {
     w.write_str("url(#")?;
-    w.write_str(id)?;
+    write_id(&mut w, id)?;
     w.write_char(')')
 }
+
+#[cfg(all(test, feature = "font-noto-sans"))]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_border_and_separator() {
+        let image_width = |svg: &str| svg.split('"').nth(1).unwrap().parse::<u32>().unwrap();
+        let plain = badge(&Style::flat(), "passing", Some("build")).unwrap();
+
+        let mut style = Style::flat();
+        style.border = Some(Border {
+            color: Color::Red,
+            width: 1,
+        });
+        style.separator = Some(Separator::Solid {
+            color: Color::Red,
+            width: 1,
+        });
+        let svg = badge(&style, "passing", Some("build")).unwrap();
+        assert!(svg.contains(r##"fill="none" stroke="#E43" stroke-width="100"/>"##));
+        assert_eq!(svg.matches(r##"fill="#E43""##).count(), 1);
+        assert_eq!(image_width(&svg), image_width(&plain));
+
+        style.separator = Some(Separator::Gap { width: 2 });
+        let svg = badge(&style, "passing", Some("build")).unwrap();
+        assert!(svg.contains(r##"fill="#000"/>"##));
+        assert_eq!(image_width(&svg), image_width(&plain) + 2);
+
+        style.separator = Some(Separator::Chevron { width: 4 });
+        let svg = badge(&style, "passing", None).unwrap();
+        assert!(!svg.contains("0l400"));
+        let svg = badge(&style, "passing", Some("build")).unwrap();
+        assert!(svg.contains(r##" 0l400 1000l-400 1000z" fill="#555"/>"##));
+    }
+
+    #[test]
+    fn test_id_prefix() {
+        let mut style = Style::classic();
+        style.id_prefix = IdPrefix::custom("a-").unwrap();
+        let svg = badge(&style, "passing", Some("build")).unwrap();
+        for id in ["m", "g", "l", "s"].iter() {
+            assert!(svg.contains(&format!(r#"id="a-{}""#, id)), "{}", id);
+        }
+        assert!(svg.contains(r##"mask="url(#a-m)""##));
+        assert!(svg.contains(r##"fill="url(#a-g)""##));
+        assert!(svg.contains(r##"href="#a-s""##));
+        assert!(!svg.contains(r##""#s""##));
+
+        style.id_prefix = IdPrefix::Hash;
+        let passing = badge(&style, "passing", Some("build")).unwrap();
+        let failing = badge(&style, "failing", Some("build")).unwrap();
+        let prefix = |svg: &str| svg.split(r#"id=""#).nth(1).unwrap()[..10].to_string();
+        assert!(prefix(&passing).starts_with('b'));
+        assert_ne!(prefix(&passing), prefix(&failing));
+        assert_eq!(
+            prefix(&passing),
+            prefix(&badge(&style, "passing", Some("build")).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_no_label_background() {
+        let mut style = Style::classic();
+        style.label_background = None;
+        let svg = badge(&style, "passing", Some("build")).unwrap();
+        // The label path is still drawn, unfilled and so black.
+        assert_eq!(svg.matches("<path").count(), 5);
+        assert_eq!(svg.matches(r#"z"/>"#).count(), 1);
+
+        style.ghost = true;
+        let svg = badge(&style, "passing", Some("build")).unwrap();
+        assert_eq!(svg.matches("<path").count(), 3);
+    }
+
+    #[test]
+    fn test_ghost() {
+        let svg = badge(&Style::ghost(), "passing", Some("build")).unwrap();
+        // No background rects, and the text has no shadow.
+        assert_eq!(svg.matches("<path").count(), 2);
+        assert_eq!(svg.matches("<use").count(), 2);
+        assert!(svg.contains(r##"fill="none" stroke="#08C" stroke-width="100"/>"##));
+        assert!(svg.contains(r##"<use href="#s" fill="#08C"/>"##));
+        assert!(svg.contains(r##"<use href="#l" fill="#555"/>"##));
+    }
+
+    #[test]
+    fn test_gradient_shape_and_target() {
+        let mut style = Style::classic();
+        let gradient = style.gradient.as_mut().unwrap();
+        gradient.shape = GradientShape::Linear(GradientDirection::ToTopLeft);
+        gradient.target = GradientTarget::Segments;
+        let svg = badge(&style, "passing", Some("build")).unwrap();
+        assert!(svg.contains(r#"<linearGradient id="g" x1="100%" y1="100%" x2="0">"#));
+        assert_eq!(svg.matches(r#"fill="url(#g)""#).count(), 2);
+
+        let gradient = style.gradient.as_mut().unwrap();
+        gradient.shape = GradientShape::Radial;
+        gradient.target = GradientTarget::Label;
+        let svg = badge(&style, "passing", None).unwrap();
+        assert!(svg.contains(r#"<radialGradient id="g">"#));
+        assert_eq!(svg.matches(r#"fill="url(#g)""#).count(), 0);
+    }
+
+    #[test]
+    fn test_text_rendering_sizes() {
+        // Badges from the `TextRendering::Glyphs` docs, with whether glyphs
+        // are smaller.
+        let badges = [
+            ("build", "passing", false),
+            ("license", "MIT", false),
+            ("version", "1.2.3", false),
+            ("coverage", "99%", true),
+            ("docs", "passing", true),
+            ("dependencies", "up to date", true),
+        ];
+        for &(label, status, smaller) in badges.iter() {
+            let mut style = Style::classic();
+            let paths = badge(&style, status, Some(label)).unwrap();
+            style.text_rendering = TextRendering::Glyphs;
+            let glyphs = badge(&style, status, Some(label)).unwrap();
+            assert_eq!(glyphs.len() < paths.len(), smaller, "{}", label);
+        }
+    }
+
+    #[test]
+    fn test_text_rendering_glyphs() {
+        let attr = |element: &str, name: &str| {
+            let value = element.split(&format!(" {}=\"", name)).nth(1)?;
+            Some(value[..value.find('"').unwrap()].to_string())
+        };
+
+        let mut style = Style::classic();
+        let paths = badge(&style, "mississippi", Some("build")).unwrap();
+        style.text_rendering = TextRendering::Glyphs;
+        let glyphs = badge(&style, "mississippi", Some("build")).unwrap();
+
+        // Each unique glyph of "build" and "mississippi" is defined once.
+        assert_eq!(glyphs.matches(r#"<path id="c"#).count(), 8);
+        assert!(glyphs.len() < paths.len());
+        assert_eq!(&glyphs[..40], &paths[..40]);
+
+        // Placing the glyph paths gives the same text paths.
+        let mut defs = alloc::collections::BTreeMap::new();
+        let mut text_id = None;
+        let mut baseline = String::new();
+        let mut placed = alloc::collections::BTreeMap::new();
+        for element in glyphs.split('<') {
+            if element.starts_with("path id=\"c") {
+                defs.insert(attr(element, "id").unwrap(), attr(element, "d").unwrap());
+            } else if element.starts_with("g id=") {
+                text_id = attr(element, "id");
+                baseline = attr(element, "transform").unwrap()["translate(0,".len()..]
+                    .trim_end_matches(')')
+                    .to_string();
+            } else if element.starts_with("use href=\"#c") {
+                let glyph = attr(element, "href").unwrap()[1..].to_string();
+                let path: &mut String = placed.entry(text_id.clone().unwrap()).or_default();
+                path.push_str(&format!("M{} {}", attr(element, "x").unwrap(), baseline));
+                path.push_str(&defs[&glyph]);
+            }
+        }
+        for (id, path) in placed.iter() {
+            assert!(
+                paths.contains(&format!(r#"<path id="{}" d="{}"/>"#, id, path)),
+                "{}",
+                id
+            );
+        }
+        assert_eq!(placed.len(), 2);
+    }
+
+    #[test]
+    fn test_font_weight_and_style() {
+        let width = |svg: &str| svg.split('"').nth(1).unwrap().parse::<u32>().unwrap();
+
+        let mut style = Style::classic();
+        let regular = badge(&style, "passing", Some("build")).unwrap();
+        style.font_weight = FontWeight::Bold;
+        let bold = badge(&style, "passing", Some("build")).unwrap();
+        style.font_weight = FontWeight::Normal;
+        style.font_style = FontStyle::Italic;
+        let italic = badge(&style, "passing", Some("build")).unwrap();
+
+        assert!(width(&bold) > width(&regular));
+        assert_ne!(italic, regular);
+        // Synthesized italic slants glyphs without changing their advance.
+        #[cfg(not(feature = "font-noto-sans-italic"))]
+        assert_eq!(width(&italic), width(&regular));
+    }
+
+    #[test]
+    fn test_style_fonts() {
+        let mut style = Style::classic();
+        style.font_weight = FontWeight::Bold;
+        style.font_style = FontStyle::Italic;
+        let face = style_font(&style);
+        assert_eq!(face.is_bold(), cfg!(feature = "font-noto-sans-bold"));
+        assert_eq!(face.is_italic(), cfg!(feature = "font-noto-sans-italic"));
+
+        // Fonts are shared by styles of the same face.
+        let mut fonts = StyleFonts::new();
+        let mut scratch = String::new();
+        for style in [Style::classic(), Style::flat(), style].iter() {
+            let mut svg = String::new();
+            let font = fonts.get(style);
+            write_badge_with_font(&mut svg, style, "passing", None, font, &mut scratch).unwrap();
+            assert_eq!(svg, badge(style, "passing", None).unwrap());
+        }
+        assert_eq!(fonts.fonts.len(), 2);
+    }
+
+    #[cfg(feature = "font-dejavu-sans")]
+    #[test]
+    fn test_shields_style() {
+        let width = |svg: &str| svg.split('"').nth(1).unwrap().parse::<u32>().unwrap();
+
+        // The widths of the same badges from shields.io.
+        let shields = badge(&Style::shields(), "MIT", Some("license")).unwrap();
+        assert_eq!(width(&shields), 78);
+        let shields = badge(&Style::shields(), "passing", Some("build")).unwrap();
+        assert!((width(&shields) as i32 - 88).abs() <= 2);
+
+        let mut style = Style::shields();
+        style.font_family = FontFamily::NotoSans;
+        assert_ne!(badge(&style, "passing", Some("build")).unwrap(), shields);
+        assert!(font_licenses().iter().any(|l| l.contains("DejaVu")));
+    }
+}
@@ -0,0 +1,436 @@
+//! Batch generation of badges from a manifest.
+//!
+//! A [`Manifest`] lists named badges and can be loaded from any serde format,
+//! such as TOML or JSON.
+//!
+//! ```toml
+//! [[badges]]
+//! name = "build"
+//! label = "build"
+//! status = "passing"
+//! color = "green"
+//! style = "flat"
+//!
+//! [[badges]]
+//! name = "license"
+//! label = "license"
+//! status = "MIT"
+//! output = "license-badge.svg"
+//! ```
+//!
+//! Badges are rendered by [`render_all`], sharing a font cache between badges
+//! of the same font face.
+//!
+//! ```rust
+//! let json = r#"{"badges": [{"name": "build", "label": "build", "status": "passing"}]}"#;
+//! let manifest: badgen::manifest::Manifest = serde_json::from_str(json).unwrap();
+//!
+//! for rendered in badgen::manifest::render_all(&manifest).unwrap() {
+//!     println!("{}: {}", rendered.badge.output().unwrap(), rendered.svg);
+//! }
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{write_badge_with_font, Color, Font, Style};
+
+/// A list of named badges.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// The badges in the manifest.
+    pub badges: Vec<ManifestBadge>,
+}
+
+impl Manifest {
+    /// Checks every badge has a relative output file within the output
+    /// directory, and that no two badges share one.
+    ///
+    /// This is done by [`render_all_with_font`] before rendering.
+    pub fn validate(&self) -> Result<(), ManifestError> {
+        let mut outputs = BTreeSet::new();
+        for badge in &self.badges {
+            let output = badge.output()?;
+            if !is_relative_output(&output) {
+                return Err(badge.error(ManifestErrorKind::InvalidOutput));
+            }
+            if !outputs.insert(output) {
+                return Err(badge.error(ManifestErrorKind::DuplicateOutput));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A named badge within a [`Manifest`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ManifestBadge {
+    /// The name of the badge.
+    ///
+    /// Badges in a manifest need either a name or an output file.
+    #[serde(default)]
+    pub name: String,
+    /// The label of the badge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// The status of the badge.
+    pub status: String,
+    /// The style of the badge, either `classic` (the default) or `flat`, or
+    /// `shields` with the `font-dejavu-sans` feature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    /// The status background color of the badge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// The label background color of the badge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label_color: Option<String>,
+    /// The icon of the badge.
+    ///
+    /// Icons are not currently rendered, and a badge with one fails to
+    /// render.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// The output file of the badge.
+    ///
+    /// Defaults to the name of the badge with an `.svg` extension.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl ManifestBadge {
+    /// Returns the style of the badge.
+    ///
+    /// Returns an error if the badge has an icon, as icons are not rendered.
+    pub fn style(&self) -> Result<Style<'_>, ManifestError> {
+        if self.icon.is_some() {
+            return Err(self.error(ManifestErrorKind::UnsupportedIcon));
+        }
+        let mut style = match self.style.as_deref() {
+            None | Some("classic") => Style::classic(),
+            Some("flat") => Style::flat(),
+            #[cfg(feature = "font-dejavu-sans")]
+            Some("shields") => Style::shields(),
+            #[cfg(not(feature = "font-dejavu-sans"))]
+            Some("shields") => {
+                return Err(self.error(ManifestErrorKind::MissingFeature("font-dejavu-sans")))
+            }
+            Some(_) => return Err(self.error(ManifestErrorKind::InvalidStyle)),
+        };
+        if let Some(color) = self.color.as_deref() {
+            style.background = Color::parse_shields(color)
+                .ok_or_else(|| self.error(ManifestErrorKind::InvalidColor))?;
+        }
+        if let Some(label_color) = self.label_color.as_deref() {
+            let label_color = Color::parse_shields(label_color)
+                .ok_or_else(|| self.error(ManifestErrorKind::InvalidLabelColor))?;
+            style.label_background = Some(label_color);
+        }
+        Ok(style)
+    }
+
+    /// Returns the output file of the badge.
+    ///
+    /// Returns an error if the badge has neither an output file nor a name.
+    pub fn output(&self) -> Result<Cow<'_, str>, ManifestError> {
+        match self.output {
+            Some(ref output) if !output.is_empty() => Ok(Cow::Borrowed(output)),
+            None if !self.name.is_empty() => Ok(Cow::Owned(format!("{}.svg", self.name))),
+            _ => Err(self.error(ManifestErrorKind::MissingOutput)),
+        }
+    }
+
+    /// Writes the SVG badge to a [`fmt::Write`] given a font and scratch
+    /// space.
+    pub fn write_badge_with_font<W, F>(
+        &self,
+        w: &mut W,
+        font: &mut F,
+        scratch: &mut String,
+    ) -> Result<(), ManifestError>
+    where
+        W: fmt::Write,
+        F: Font,
+    {
+        let style = self.style()?;
+        write_badge_with_font(
+            w,
+            &style,
+            &self.status,
+            self.label.as_deref(),
+            font,
+            scratch,
+        )
+        .map_err(|_| self.error(ManifestErrorKind::Format))
+    }
+
+    fn error(&self, kind: ManifestErrorKind) -> ManifestError {
+        ManifestError {
+            name: self.name.clone(),
+            kind,
+        }
+    }
+}
+
+/// Returns `true` if an output file is a relative path with no `..`
+/// components, so stays within the output directory.
+fn is_relative_output(output: &str) -> bool {
+    let absolute = output.starts_with(['/', '\\']) || output.get(1..2) == Some(":");
+    !absolute && output.split(['/', '\\']).all(|c| c != "..")
+}
+
+/// A badge rendered from a [`Manifest`].
+#[derive(Debug, Clone)]
+pub struct RenderedBadge<'a> {
+    /// The badge from the manifest.
+    pub badge: &'a ManifestBadge,
+    /// The rendered SVG.
+    pub svg: String,
+}
+
+/// Renders all badges in a manifest.
+///
+/// Uses the default fonts provided by this library, with a font cache for
+/// each font face the badge styles use, see [`StyleFonts`](crate::StyleFonts).
+///
+/// The manifest is checked with [`Manifest::validate`] first.
+#[cfg(any(feature = "font-noto-sans", feature = "font-dejavu-sans"))]
+pub fn render_all(manifest: &Manifest) -> Result<Vec<RenderedBadge<'_>>, ManifestError> {
+    let mut fonts = crate::StyleFonts::new();
+    render_each(manifest, |badge, svg, scratch| {
+        let font = fonts.get(&badge.style()?);
+        badge.write_badge_with_font(svg, font, scratch)
+    })
+}
+
+/// Renders all badges in a manifest given a font.
+///
+/// The manifest is checked with [`Manifest::validate`] first.
+pub fn render_all_with_font<'a, F>(
+    manifest: &'a Manifest,
+    font: &mut F,
+) -> Result<Vec<RenderedBadge<'a>>, ManifestError>
+where
+    F: Font,
+{
+    render_each(manifest, |badge, svg, scratch| {
+        badge.write_badge_with_font(svg, font, scratch)
+    })
+}
+
+fn render_each<'a, R>(
+    manifest: &'a Manifest,
+    mut render: R,
+) -> Result<Vec<RenderedBadge<'a>>, ManifestError>
+where
+    R: FnMut(&ManifestBadge, &mut String, &mut String) -> Result<(), ManifestError>,
+{
+    manifest.validate()?;
+    let mut scratch = String::with_capacity(4096);
+    manifest
+        .badges
+        .iter()
+        .map(|badge| {
+            let mut svg = String::with_capacity(8192);
+            render(badge, &mut svg, &mut scratch)?;
+            Ok(RenderedBadge { badge, svg })
+        })
+        .collect()
+}
+
+/// An error rendering a badge from a [`Manifest`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestError {
+    name: String,
+    kind: ManifestErrorKind,
+}
+
+impl ManifestError {
+    /// Returns the name of the badge that failed.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the kind of error.
+    pub fn kind(&self) -> ManifestErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.name.is_empty() {
+            write!(f, "unnamed badge: {}", self.kind)
+        } else {
+            write!(f, "badge `{}`: {}", self.name, self.kind)
+        }
+    }
+}
+
+/// The kind of a [`ManifestError`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum ManifestErrorKind {
+    /// The style name is unknown.
+    InvalidStyle,
+    /// The style needs a cargo feature that is not enabled.
+    MissingFeature(&'static str),
+    /// The status color is invalid.
+    InvalidColor,
+    /// The label color is invalid.
+    InvalidLabelColor,
+    /// The badge has neither a name nor an output file.
+    MissingOutput,
+    /// The output file is the same as that of an earlier badge.
+    DuplicateOutput,
+    /// The output file is absolute or outside the output directory.
+    InvalidOutput,
+    /// The badge has an icon, which is not rendered.
+    UnsupportedIcon,
+    /// The badge failed to format.
+    Format,
+}
+
+impl fmt::Display for ManifestErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidStyle => f.write_str("invalid style"),
+            Self::MissingFeature(feature) => {
+                write!(f, "style requires the `{}` feature", feature)
+            }
+            Self::InvalidColor => f.write_str("invalid color"),
+            Self::InvalidLabelColor => f.write_str("invalid label color"),
+            Self::MissingOutput => f.write_str("missing name or output"),
+            Self::DuplicateOutput => f.write_str("duplicate output"),
+            Self::InvalidOutput => f.write_str("output must be a relative path without `..`"),
+            Self::UnsupportedIcon => f.write_str("icons are not supported"),
+            Self::Format => f.write_str("failed to format"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fn manifest() -> Manifest {
+        serde_json::from_str(
+            r#"{"badges": [
+                {"name": "build", "label": "build", "status": "passing", "color": "green"},
+                {"name": "license", "status": "MIT", "style": "flat", "output": "lic.svg"}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_manifest_badge() {
+        let manifest = manifest();
+        let build = &manifest.badges[0];
+        assert_eq!(build.output().unwrap(), "build.svg");
+        assert_eq!(build.style().unwrap().background, Color::Green);
+        let license = &manifest.badges[1];
+        assert_eq!(license.output().unwrap(), "lic.svg");
+        assert!(license.style().unwrap().gradient.is_none());
+
+        let invalid = ManifestBadge {
+            name: "bad".into(),
+            color: Some("nope".into()),
+            ..ManifestBadge::default()
+        };
+        let err = invalid.style().unwrap_err();
+        assert_eq!(err.name(), "bad");
+        assert_eq!(err.kind(), ManifestErrorKind::InvalidColor);
+
+        let icon = ManifestBadge {
+            icon: Some("github".into()),
+            ..ManifestBadge::default()
+        };
+        let err = icon.style().unwrap_err();
+        assert_eq!(err.kind(), ManifestErrorKind::UnsupportedIcon);
+
+        let shields = ManifestBadge {
+            name: "shields".into(),
+            style: Some("shields".into()),
+            ..ManifestBadge::default()
+        };
+        #[cfg(feature = "font-dejavu-sans")]
+        assert_eq!(
+            shields.style().unwrap().font_family,
+            crate::FontFamily::DejaVuSans
+        );
+        #[cfg(not(feature = "font-dejavu-sans"))]
+        assert_eq!(
+            shields.style().unwrap_err().to_string(),
+            "badge `shields`: style requires the `font-dejavu-sans` feature"
+        );
+    }
+
+    #[test]
+    fn test_manifest_validate() {
+        assert!(manifest().validate().is_ok());
+
+        let unnamed: Manifest =
+            serde_json::from_str(r#"{"badges": [{"status": "a"}, {"status": "b"}]}"#).unwrap();
+        let err = unnamed.validate().unwrap_err();
+        assert_eq!(err.kind(), ManifestErrorKind::MissingOutput);
+        assert_eq!(err.to_string(), "unnamed badge: missing name or output");
+
+        let duplicate: Manifest = serde_json::from_str(
+            r#"{"badges": [
+                {"name": "a", "status": "a"},
+                {"name": "b", "status": "b", "output": "a.svg"}
+            ]}"#,
+        )
+        .unwrap();
+        let err = duplicate.validate().unwrap_err();
+        assert_eq!(err.name(), "b");
+        assert_eq!(err.kind(), ManifestErrorKind::DuplicateOutput);
+
+        for output in [
+            "/tmp/a.svg",
+            "../a.svg",
+            "a/../../b.svg",
+            "a\\..\\..\\b.svg",
+            "C:a.svg",
+        ] {
+            let escaping = Manifest {
+                badges: vec![ManifestBadge {
+                    name: "a".into(),
+                    output: Some(output.into()),
+                    ..ManifestBadge::default()
+                }],
+            };
+            let err = escaping.validate().unwrap_err();
+            assert_eq!(err.kind(), ManifestErrorKind::InvalidOutput, "{}", output);
+        }
+        let nested: Manifest = serde_json::from_str(
+            r#"{"badges": [{"name": "a", "status": "a", "output": "b/a..svg"}]}"#,
+        )
+        .unwrap();
+        assert!(nested.validate().is_ok());
+    }
+
+    #[cfg(feature = "font-noto-sans")]
+    #[test]
+    fn test_render_all() {
+        let manifest = manifest();
+        let rendered = render_all(&manifest).unwrap();
+        assert_eq!(rendered.len(), 2);
+        assert_eq!(rendered[1].badge.name, "license");
+        assert_eq!(
+            rendered[1].svg,
+            crate::badge(&Style::flat(), "MIT", None).unwrap()
+        );
+    }
+}
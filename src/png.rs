@@ -0,0 +1,677 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use tiny_skia::{
+    BlendMode, FillRule, GradientStop, LinearGradient, Mask, Paint, Path, PathBuilder, Pixmap,
+    RadialGradient, Rect, Shader, SpreadMode, Stroke, Transform,
+};
+use ttf_parser::OutlineBuilder;
+
+use crate::{
+    separator_spacing, text_origin, text_spacing, Color, Colors, Font, Gradient, GradientShape,
+    GradientTarget, Layout, Opacity, Point, Separator, Style, MIDDLE_MARGIN, VIEWBOX_HEIGHT,
+    VIEWBOX_USER_SCALE,
+};
+
+/// An error rasterizing a badge to a PNG.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PngError {
+    /// The badge text failed to format.
+    Format(fmt::Error),
+    /// The badge size is invalid for the given scale.
+    InvalidSize,
+    /// The PNG failed to encode.
+    Encode,
+    /// The font has glyph paths but no outlines, see [`Font::outline_glyph`].
+    MissingOutline,
+}
+
+impl fmt::Display for PngError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Format(_) => f.write_str("badge text failed to format"),
+            Self::InvalidSize => f.write_str("badge size is invalid for the given scale"),
+            Self::Encode => f.write_str("badge png failed to encode"),
+            Self::MissingOutline => f.write_str("font does not support glyph outlines"),
+        }
+    }
+}
+
+impl From<fmt::Error> for PngError {
+    fn from(err: fmt::Error) -> Self {
+        Self::Format(err)
+    }
+}
+
+/// Rasterize a badge to a PNG given a style, status, optional label and
+/// scale factor.
+///
+/// A scale of `1.0` produces an image the same size as the SVG badge, while
+/// `2.0` produces one suitable for HiDPI displays.
+///
+/// Uses the default font provided by this library.
+///
+/// # Example
+///
+/// ```rust
+/// let png = badgen::render_png(
+///     &badgen::Style::classic(),
+///     "status",
+///     Some("label"),
+///     2.0,
+/// ).unwrap();
+///
+/// assert_eq!(&png[1..4], b"PNG");
+/// ```
+#[cfg(any(feature = "font-noto-sans", feature = "font-dejavu-sans"))]
+pub fn render_png<S>(
+    style: &Style<'_>,
+    status: S,
+    label: Option<&str>,
+    scale: f32,
+) -> Result<Vec<u8>, PngError>
+where
+    S: fmt::Display,
+{
+    let ttf_font = crate::style_font(style);
+    let mut font = crate::font_for_style(&ttf_font, style);
+    render_png_with_font(style, status, label, &mut font, scale)
+}
+
+/// Rasterize a badge to a PNG given a style, status, optional label, font and
+/// scale factor.
+///
+/// The font must support [`Font::outline_glyph`], returning
+/// [`PngError::MissingOutline`] for a glyph with a path but no outline.
+pub fn render_png_with_font<S, F>(
+    style: &Style<'_>,
+    status: S,
+    label: Option<&str>,
+    font: &mut F,
+    scale: f32,
+) -> Result<Vec<u8>, PngError>
+where
+    S: fmt::Display,
+    F: Font,
+{
+    let text_spacing = text_spacing(style);
+    let mut next_text_origin = text_origin(font);
+
+    // If a label is specified, outline and calculate the width.
+    let mut label_builder = PathBuilder::new();
+    let label_width = if let Some(label) = label {
+        let label_width = outline_text(
+            font,
+            next_text_origin,
+            label,
+            text_spacing,
+            &mut label_builder,
+        )?;
+        next_text_origin.x += label_width + MIDDLE_MARGIN + separator_spacing(style);
+        Some(label_width)
+    } else {
+        None
+    };
+
+    let label_path = label_builder.finish();
+    let has_label = label_path.is_some();
+
+    let mut status_builder = PathBuilder::new();
+    let status_width = outline_text(
+        font,
+        next_text_origin,
+        &status,
+        text_spacing,
+        &mut status_builder,
+    )?;
+    let status_path = status_builder.finish();
+
+    let layout = Layout::new(style, label_width.filter(|_| has_label), status_width);
+
+    ///////////////////////////////////////////////////////////////////////////
+
+    let pixmap_width = (layout.image_size.x as f32 * scale).round() as u32;
+    let pixmap_height = (layout.image_size.y as f32 * scale).round() as u32;
+    let mut pixmap = Pixmap::new(pixmap_width, pixmap_height).ok_or(PngError::InvalidSize)?;
+
+    // Maps viewbox units to pixels.
+    let transform = Transform::from_scale(
+        pixmap_width as f32 / layout.viewbox_size.x as f32,
+        pixmap_height as f32 / layout.viewbox_size.y as f32,
+    );
+
+    let viewbox_rect = rect(Point { x: 0, y: 0 }, layout.viewbox_size)?;
+
+    ///////////////////////////////////////////////////////////////////////////
+
+    let mask = if style.gradient.is_some() || style.border_radius > 0 {
+        let radius = (style.border_radius as u32 * VIEWBOX_USER_SCALE) as f32;
+        let mut mask = Mask::new(pixmap_width, pixmap_height).ok_or(PngError::InvalidSize)?;
+        let path = rounded_rect_path(viewbox_rect, radius).ok_or(PngError::InvalidSize)?;
+        mask.fill_path(&path, FillRule::Winding, true, transform);
+        Some(mask)
+    } else {
+        None
+    };
+
+    ///////////////////////////////////////////////////////////////////////////
+
+    let colors = Colors::new(style);
+
+    // Without a label background, the label is black as in the SVG, other
+    // than for ghost badges.
+    let label_fill = match colors.label_background {
+        Some(label_background) => Some(label_background),
+        None if !style.ghost => Some(Color::Black),
+        None => None,
+    };
+
+    if has_label {
+        if let Some(label_fill) = label_fill {
+            let label_rect = rect(
+                Point { x: 0, y: 0 },
+                Point {
+                    x: layout.label_rect_width,
+                    y: VIEWBOX_HEIGHT,
+                },
+            )?;
+            pixmap.fill_rect(
+                label_rect,
+                &solid_paint(label_fill, 1.0),
+                transform,
+                mask.as_ref(),
+            );
+        }
+    }
+
+    let status_rect = rect(
+        Point {
+            x: layout.label_rect_width,
+            y: 0,
+        },
+        Point {
+            x: layout.status_rect_width,
+            y: VIEWBOX_HEIGHT,
+        },
+    )?;
+    if let Some(status_background) = colors.status_background {
+        pixmap.fill_rect(
+            status_rect,
+            &solid_paint(status_background, 1.0),
+            transform,
+            mask.as_ref(),
+        );
+    }
+
+    if has_label {
+        match style.separator {
+            Some(Separator::Solid { color, width }) => {
+                let width = width as u32 * VIEWBOX_USER_SCALE;
+                let separator_rect = rect(
+                    Point {
+                        x: layout.label_rect_width.saturating_sub(width / 2),
+                        y: 0,
+                    },
+                    Point {
+                        x: width,
+                        y: VIEWBOX_HEIGHT,
+                    },
+                )?;
+                pixmap.fill_rect(
+                    separator_rect,
+                    &solid_paint(color, 1.0),
+                    transform,
+                    mask.as_ref(),
+                );
+            }
+            Some(Separator::Chevron { width }) => {
+                if let Some(label_background) = colors.label_background {
+                    let x = layout.label_rect_width as f32;
+                    let width = (width as u32 * VIEWBOX_USER_SCALE) as f32;
+                    let mut pb = PathBuilder::new();
+                    pb.move_to(x, 0.0);
+                    pb.line_to(x + width, VIEWBOX_HEIGHT as f32 / 2.0);
+                    pb.line_to(x, VIEWBOX_HEIGHT as f32);
+                    pb.close();
+                    if let Some(path) = pb.finish() {
+                        pixmap.fill_path(
+                            &path,
+                            &solid_paint(label_background, 1.0),
+                            FillRule::Winding,
+                            transform,
+                            mask.as_ref(),
+                        );
+                    }
+                }
+            }
+            Some(Separator::Gap { .. }) | None => {}
+        }
+    }
+
+    if let Some(ref gradient) = style.gradient {
+        // Without a label, the label rect is empty.
+        let label_rect = rect(
+            Point { x: 0, y: 0 },
+            Point {
+                x: layout.label_rect_width,
+                y: VIEWBOX_HEIGHT,
+            },
+        )
+        .ok();
+        let (badge, label, status) = match gradient.target {
+            GradientTarget::Badge => (true, false, false),
+            GradientTarget::Segments => (false, has_label, true),
+            GradientTarget::Label => (false, has_label, false),
+            GradientTarget::Status => (false, false, true),
+        };
+        let rects = [
+            (badge, Some(viewbox_rect)),
+            (label, label_rect),
+            (status, Some(status_rect)),
+        ];
+        let target_rects = rects
+            .iter()
+            .filter(|(enabled, _)| *enabled)
+            .filter_map(|(_, rect)| *rect);
+        for target_rect in target_rects {
+            let paint = Paint {
+                shader: gradient_shader(gradient, target_rect)?,
+                ..Paint::default()
+            };
+            pixmap.fill_rect(target_rect, &paint, transform, mask.as_ref());
+        }
+    }
+
+    // Like the SVG mask, a gap separator clears everything beneath it.
+    if let (true, Some(Separator::Gap { width })) = (has_label, style.separator) {
+        let gap_rect = rect(
+            Point {
+                x: layout.label_rect_width,
+                y: 0,
+            },
+            Point {
+                x: width as u32 * VIEWBOX_USER_SCALE,
+                y: VIEWBOX_HEIGHT,
+            },
+        )?;
+        let paint = Paint {
+            blend_mode: BlendMode::Clear,
+            ..Paint::default()
+        };
+        pixmap.fill_rect(gap_rect, &paint, transform, None);
+    }
+
+    if let Some(border) = colors.border {
+        // Inset the stroke so it is drawn entirely inside the badge.
+        let width = (border.width as u32 * VIEWBOX_USER_SCALE) as f32;
+        let border_rect = Rect::from_ltrb(
+            width / 2.0,
+            width / 2.0,
+            viewbox_rect.right() - width / 2.0,
+            viewbox_rect.bottom() - width / 2.0,
+        )
+        .ok_or(PngError::InvalidSize)?;
+        let radius = (style.border_radius as u32 * VIEWBOX_USER_SCALE) as f32 - width / 2.0;
+        let path = rounded_rect_path(border_rect, radius).ok_or(PngError::InvalidSize)?;
+        let stroke = Stroke {
+            width,
+            ..Stroke::default()
+        };
+        pixmap.stroke_path(
+            &path,
+            &solid_paint(border.color, 1.0),
+            &stroke,
+            transform,
+            None,
+        );
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+
+    if let Some(label_path) = label_path {
+        fill_text_path(
+            &mut pixmap,
+            &label_path,
+            style,
+            colors.label_text,
+            transform,
+        );
+    }
+
+    if let Some(status_path) = status_path {
+        fill_text_path(
+            &mut pixmap,
+            &status_path,
+            style,
+            colors.status_text,
+            transform,
+        );
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+
+    pixmap.encode_png().map_err(|_| PngError::Encode)
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+fn fill_text_path(
+    pixmap: &mut Pixmap,
+    path: &Path,
+    style: &Style<'_>,
+    text_color: Color<'_>,
+    transform: Transform,
+) {
+    if !style.text_shadow_opacity.is_transparent() {
+        let shadow_offset = (style.text_shadow_offset as u32 * VIEWBOX_USER_SCALE) as f32;
+        pixmap.fill_path(
+            path,
+            &solid_paint(
+                style.text_shadow_color,
+                opacity_value(style.text_shadow_opacity),
+            ),
+            FillRule::Winding,
+            transform.pre_translate(shadow_offset, shadow_offset),
+            None,
+        );
+    }
+    pixmap.fill_path(
+        path,
+        &solid_paint(text_color, 1.0),
+        FillRule::Winding,
+        transform,
+        None,
+    );
+}
+
+/// Creates a shader for a gradient applied to a rect.
+fn gradient_shader(gradient: &Gradient<'_>, rect: Rect) -> Result<Shader<'static>, PngError> {
+    // Like SVG, a missing stop color defaults to black.
+    let stops = gradient
+        .stops
+        .iter()
+        .map(|stop| {
+            let color = stop.color.map_or(tiny_skia::Color::BLACK, color_value);
+            GradientStop::new(
+                stop.offset,
+                with_opacity(color, opacity_value(stop.opacity)),
+            )
+        })
+        .collect();
+    // Like SVG, the gradient is defined in the unit space of the rect.
+    let unit_transform =
+        Transform::from_row(rect.width(), 0.0, 0.0, rect.height(), rect.x(), rect.y());
+    let shader = match gradient.shape {
+        GradientShape::Linear(direction) => {
+            let [start, end] = direction.points();
+            let point =
+                |p: Point<u8>| tiny_skia::Point::from_xy(p.x as f32 / 100.0, p.y as f32 / 100.0);
+            LinearGradient::new(
+                point(start),
+                point(end),
+                stops,
+                SpreadMode::Pad,
+                unit_transform,
+            )
+        }
+        GradientShape::Radial => {
+            let center = tiny_skia::Point::from_xy(0.5, 0.5);
+            RadialGradient::new(center, center, 0.5, stops, SpreadMode::Pad, unit_transform)
+        }
+    };
+    shader.ok_or(PngError::InvalidSize)
+}
+
+fn rect(origin: Point, size: Point) -> Result<Rect, PngError> {
+    Rect::from_xywh(
+        origin.x as f32,
+        origin.y as f32,
+        size.x as f32,
+        size.y as f32,
+    )
+    .ok_or(PngError::InvalidSize)
+}
+
+fn rounded_rect_path(rect: Rect, radius: f32) -> Option<Path> {
+    let radius = radius.min(rect.width() / 2.0).min(rect.height() / 2.0);
+    if radius <= 0.0 {
+        return Some(PathBuilder::from_rect(rect));
+    }
+    // Control point distance approximating a quarter circle with a cubic.
+    let k = radius * 0.552_284_8;
+    let (l, t, r, b) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+    let mut pb = PathBuilder::new();
+    pb.move_to(l + radius, t);
+    pb.line_to(r - radius, t);
+    pb.cubic_to(r - radius + k, t, r, t + radius - k, r, t + radius);
+    pb.line_to(r, b - radius);
+    pb.cubic_to(r, b - radius + k, r - radius + k, b, r - radius, b);
+    pb.line_to(l + radius, b);
+    pb.cubic_to(l + radius - k, b, l, b - radius + k, l, b - radius);
+    pb.line_to(l, t + radius);
+    pb.cubic_to(l, t + radius - k, l + radius - k, t, l + radius, t);
+    pb.close();
+    pb.finish()
+}
+
+fn solid_paint(color: Color<'_>, opacity: f32) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(with_opacity(color_value(color), opacity));
+    paint.anti_alias = true;
+    paint
+}
+
+fn with_opacity(mut color: tiny_skia::Color, opacity: f32) -> tiny_skia::Color {
+    color.apply_opacity(opacity);
+    color
+}
+
+/// Converts a color to a raster color, defaulting to black if invalid.
+fn color_value(color: Color<'_>) -> tiny_skia::Color {
+    match color.to_rgb() {
+        Some([r, g, b]) => tiny_skia::Color::from_rgba8(r, g, b, 255),
+        None => tiny_skia::Color::BLACK,
+    }
+}
+
+/// Converts an opacity to a float, defaulting to opaque if invalid.
+fn opacity_value(opacity: Opacity<'_>) -> f32 {
+    opacity.to_f32().unwrap_or(1.0)
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Outlines text into a path builder in viewbox units, returning the width.
+///
+/// Glyphs are positioned identically to the SVG text paths.
+fn outline_text<F, D>(
+    font: &mut F,
+    origin: Point,
+    text: D,
+    letter_spacing: f32,
+    builder: &mut PathBuilder,
+) -> Result<u32, PngError>
+where
+    F: Font,
+    D: fmt::Display,
+{
+    let letter_spacing = letter_spacing * font.scale();
+
+    let mut text_outline = TextOutlineWrite {
+        builder,
+        font,
+        letter_spacing,
+        missing_outline: false,
+        next_glyph_origin: Point {
+            x: origin.x as f32 + letter_spacing,
+            y: origin.y as f32,
+        },
+    };
+
+    if let Err(err) = write!(text_outline, "{}", text) {
+        return Err(if text_outline.missing_outline {
+            PngError::MissingOutline
+        } else {
+            PngError::Format(err)
+        });
+    }
+
+    Ok(text_outline.next_glyph_origin.x as u32 - origin.x)
+}
+
+struct TextOutlineWrite<'a, F> {
+    builder: &'a mut PathBuilder,
+    font: &'a mut F,
+    letter_spacing: f32,
+    missing_outline: bool,
+    next_glyph_origin: Point<f32>,
+}
+
+impl<'a, F> fmt::Write for TextOutlineWrite<'a, F>
+where
+    F: Font,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            let (hor_advance, has_path) = match self.font.render_glyph(c) {
+                Some(glyph) => (glyph.hor_advance, glyph.path.is_some()),
+                None => continue,
+            };
+            let mut sink = GlyphSink {
+                builder: self.builder,
+                origin: self.next_glyph_origin,
+                scale: self.font.scale(),
+            };
+            if !self.font.outline_glyph(c, &mut sink) && has_path {
+                self.missing_outline = true;
+                return Err(fmt::Error);
+            }
+            self.next_glyph_origin.x += hor_advance + self.letter_spacing;
+        }
+        Ok(())
+    }
+}
+
+/// Positions and scales glyph outlines from font units into viewbox units.
+struct GlyphSink<'a> {
+    builder: &'a mut PathBuilder,
+    origin: Point<f32>,
+    scale: f32,
+}
+
+impl<'a> GlyphSink<'a> {
+    #[inline]
+    fn x(&self, x: f32) -> f32 {
+        self.origin.x + x * self.scale
+    }
+
+    #[inline]
+    fn y(&self, y: f32) -> f32 {
+        self.origin.y - y * self.scale
+    }
+}
+
+impl<'a> OutlineBuilder for GlyphSink<'a> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = (self.x(x), self.y(y));
+        self.builder.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = (self.x(x), self.y(y));
+        self.builder.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1, x, y) = (self.x(x1), self.y(y1), self.x(x), self.y(y));
+        self.builder.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = (self.x(x1), self.y(y1));
+        let (x2, y2, x, y) = (self.x(x2), self.y(y2), self.x(x), self.y(y));
+        self.builder.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_value() {
+        let c = color_value(Color::Custom("fff"));
+        assert_eq!(c.to_color_u8().red(), 255);
+        let c = color_value(Color::Custom("0a1B2c"));
+        assert_eq!(c.to_color_u8().green(), 0x1b);
+        assert_eq!(color_value(Color::Custom("zzz")), tiny_skia::Color::BLACK);
+    }
+
+    #[cfg(feature = "font-noto-sans")]
+    #[test]
+    fn test_render_png_scale() {
+        let png = render_png(&Style::classic(), "passing", Some("build"), 1.0).unwrap();
+        let png_2x = render_png(&Style::classic(), "passing", Some("build"), 2.0).unwrap();
+        let width = |png: &[u8]| u32::from_be_bytes([png[16], png[17], png[18], png[19]]);
+        let height = |png: &[u8]| u32::from_be_bytes([png[20], png[21], png[22], png[23]]);
+        assert_eq!(height(&png), 20);
+        assert_eq!(height(&png_2x), 40);
+        assert_eq!(width(&png) * 2, width(&png_2x));
+    }
+
+    #[cfg(feature = "font-noto-sans")]
+    #[test]
+    fn test_render_png_no_label_background() {
+        let label_pixel = |style: &Style<'_>| {
+            let png = render_png(style, "passing", Some("build"), 1.0).unwrap();
+            let pixmap = Pixmap::decode_png(&png).unwrap();
+            pixmap.pixel(3, 10).unwrap()
+        };
+
+        // Drawn as with a black label background, matching the SVG.
+        let mut style = Style::flat();
+        style.label_background = Some(Color::Black);
+        let black = label_pixel(&style);
+        style.label_background = None;
+        assert_eq!(label_pixel(&style), black);
+        assert_eq!(black.alpha(), 255);
+
+        style.ghost = true;
+        assert_eq!(label_pixel(&style).alpha(), 0);
+    }
+
+    #[test]
+    fn test_render_png_missing_outline() {
+        struct PathOnlyFont;
+
+        impl Font for PathOnlyFont {
+            fn height(&self) -> u32 {
+                110
+            }
+
+            fn render_glyph(&mut self, _c: char) -> Option<crate::FontGlyph<'_>> {
+                Some(crate::FontGlyph {
+                    path: Some("M0 0h10v10h-10z"),
+                    hor_advance: 10.0,
+                })
+            }
+        }
+
+        let err = render_png_with_font(&Style::flat(), "passing", None, &mut PathOnlyFont, 1.0);
+        assert!(matches!(err, Err(PngError::MissingOutline)));
+    }
+
+    #[test]
+    fn test_gradient_shader() {
+        let target = Rect::from_xywh(0.0, 0.0, 100.0, 20.0).unwrap();
+        let mut gradient = Gradient::linear(Color::Red, Some(Color::Blue), Opacity::raw("1"));
+        assert!(gradient_shader(&gradient, target).is_ok());
+        gradient.shape = GradientShape::Radial;
+        assert!(gradient_shader(&gradient, target).is_ok());
+    }
+}
@@ -0,0 +1,206 @@
+//! Presets for common semantic badges.
+//!
+//! Each preset provides the label, formatted status and color of a badge,
+//! which are applied on top of a base [`Style`].
+//!
+//! ```rust
+//! use badgen::presets::{self, BuildState};
+//!
+//! let badge = presets::build_status(BuildState::Passing)
+//!     .badge(&badgen::Style::flat())
+//!     .unwrap();
+//!
+//! println!("{}", badge);
+//! ```
+
+#[cfg(any(feature = "font-noto-sans", feature = "font-dejavu-sans"))]
+use alloc::string::String;
+use core::fmt;
+
+use crate::format::{Count, Percent, Version};
+use crate::{Color, Style};
+
+/// A semantic badge with a label, status and color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Preset<S> {
+    /// The label of the badge.
+    pub label: &'static str,
+    /// The status of the badge.
+    pub status: S,
+    /// The status background color of the badge.
+    pub color: Color<'static>,
+}
+
+impl<S> Preset<S>
+where
+    S: fmt::Display,
+{
+    /// Returns the base style with the preset color applied.
+    pub fn style<'a>(&self, base: &Style<'a>) -> Style<'a> {
+        Style {
+            background: self.color,
+            ..base.clone()
+        }
+    }
+
+    /// Generate an SVG badge for the preset given a base style.
+    ///
+    /// Uses the default font provided by this library.
+    #[cfg(any(feature = "font-noto-sans", feature = "font-dejavu-sans"))]
+    pub fn badge(&self, base: &Style<'_>) -> Result<String, fmt::Error> {
+        crate::badge(&self.style(base), &self.status, Some(self.label))
+    }
+
+    /// Writes an SVG badge for the preset to a [`fmt::Write`] given a base
+    /// style.
+    ///
+    /// Uses the default font provided by this library.
+    #[cfg(any(feature = "font-noto-sans", feature = "font-dejavu-sans"))]
+    pub fn write_badge<W>(&self, w: &mut W, base: &Style<'_>) -> Result<(), fmt::Error>
+    where
+        W: fmt::Write,
+    {
+        crate::write_badge(w, &self.style(base), &self.status, Some(self.label))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// The state of a build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildState {
+    /// `passing`
+    Passing,
+    /// `failing`
+    Failing,
+    /// `error`
+    Error,
+    /// `pending`
+    Pending,
+    /// `unknown`
+    Unknown,
+}
+
+impl BuildState {
+    /// Returns the status text for the build state.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Passing => "passing",
+            Self::Failing => "failing",
+            Self::Error => "error",
+            Self::Pending => "pending",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// Returns the color for the build state.
+    pub fn color(&self) -> Color<'static> {
+        match self {
+            Self::Passing => Color::Green,
+            Self::Failing | Self::Error => Color::Red,
+            Self::Pending => Color::Yellow,
+            Self::Unknown => Color::Grey,
+        }
+    }
+}
+
+impl fmt::Display for BuildState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// A `build` badge.
+pub fn build_status(state: BuildState) -> Preset<BuildState> {
+    Preset {
+        label: "build",
+        status: state,
+        color: state.color(),
+    }
+}
+
+/// A `coverage` badge given a percentage between `0` and `100`.
+///
+/// The color moves from green to red as coverage decreases.
+pub fn coverage(percent: f32) -> Preset<Percent> {
+    let color = if percent >= 90.0 {
+        Color::Green
+    } else if percent >= 75.0 {
+        Color::Yellow
+    } else if percent >= 60.0 {
+        Color::Orange
+    } else {
+        Color::Red
+    };
+    Preset {
+        label: "coverage",
+        status: Percent::new(percent),
+        color,
+    }
+}
+
+/// A `version` badge.
+///
+/// Pre-release versions (those containing a `-`) are colored orange.
+pub fn version(version: &str) -> Preset<Version<'_>> {
+    let color = if version.contains('-') {
+        Color::Orange
+    } else {
+        Color::Blue
+    };
+    Preset {
+        label: "version",
+        status: Version(version),
+        color,
+    }
+}
+
+/// A `license` badge.
+pub fn license(license: &str) -> Preset<&str> {
+    Preset {
+        label: "license",
+        status: license,
+        color: Color::Blue,
+    }
+}
+
+/// A `downloads` badge.
+pub fn downloads(count: u64) -> Preset<Count> {
+    Preset {
+        label: "downloads",
+        status: Count(count),
+        color: Color::Green,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_presets() {
+        let build = build_status(BuildState::Failing);
+        assert_eq!(build.label, "build");
+        assert_eq!(build.status.to_string(), "failing");
+        assert_eq!(build.color, Color::Red);
+
+        assert_eq!(coverage(93.4).color, Color::Green);
+        assert_eq!(coverage(80.0).color, Color::Yellow);
+        assert_eq!(coverage(12.0).status.to_string(), "12%");
+
+        assert_eq!(version("1.2.3").status.to_string(), "v1.2.3");
+        assert_eq!(version("1.2.3-rc.1").color, Color::Orange);
+
+        assert_eq!(downloads(12_345).status.to_string(), "12.3k");
+    }
+
+    #[test]
+    fn test_preset_style() {
+        let style = license("MIT").style(&Style::flat());
+        assert_eq!(style.background, Color::Blue);
+        assert_eq!(style.border_radius, Style::flat().border_radius);
+    }
+}
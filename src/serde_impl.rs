@@ -0,0 +1,536 @@
+use alloc::borrow::Cow;
+use core::fmt;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use alloc::vec::Vec;
+
+use crate::{
+    Border, Color, CustomPrefix, FontFamily, FontStyle, FontWeight, Gradient, GradientStops,
+    IdPrefix, Opacity, Separator, Style, StyleBuf, TextRendering,
+};
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl<'a> Serialize for Color<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.name() {
+            Some(name) => serializer.serialize_str(name),
+            None => serializer.collect_str(self),
+        }
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for Color<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ColorVisitor;
+
+        impl<'de> Visitor<'de> for ColorVisitor {
+            type Value = Color<'de>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a named color or RGB hex value")
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Color::parse(v).ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                // Custom colors can't borrow from transient input, so are
+                // converted to RGB components.
+                Color::parse(v)
+                    .and_then(|color| color.into_owned().ok())
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_str(ColorVisitor)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl<'a> Serialize for Opacity<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for Opacity<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct OpacityVisitor;
+
+        impl<'de> Visitor<'de> for OpacityVisitor {
+            type Value = Opacity<'de>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an opacity between 0 and 1")
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Opacity::parse(v).ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                // Opacities can't borrow from transient input, so are converted
+                // to numeric values.
+                Opacity::parse(v)
+                    .and_then(|opacity| opacity.into_owned().ok())
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                match v {
+                    0 => Ok(Opacity::raw("0")),
+                    1 => Ok(Opacity::raw("1")),
+                    _ => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+                }
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Opacity::from_f32(v as f32)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Float(v), &self))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                match v {
+                    0 | 1 => self.visit_u64(v as u64),
+                    _ => Err(E::invalid_value(de::Unexpected::Signed(v), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(OpacityVisitor)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl<'a> Serialize for GradientStops<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for GradientStops<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer).map(GradientStops::Owned)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum StyleBase {
+    Classic,
+    Flat,
+    Ghost,
+    Shields,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct StyleDef<'a> {
+    base: Option<StyleBase>,
+    height: Option<u16>,
+    border_radius: Option<u16>,
+    #[serde(borrow)]
+    background: Option<Color<'a>>,
+    #[serde(borrow)]
+    text_color: Option<Color<'a>>,
+    text_spacing: Option<f32>,
+    #[serde(borrow)]
+    text_shadow_color: Option<Color<'a>>,
+    #[serde(borrow)]
+    text_shadow_opacity: Option<Opacity<'a>>,
+    text_shadow_offset: Option<u16>,
+    #[serde(borrow, default, deserialize_with = "explicit_option")]
+    label_background: Option<Option<Color<'a>>>,
+    #[serde(borrow, default, deserialize_with = "explicit_option")]
+    label_text_color: Option<Option<Color<'a>>>,
+    #[serde(borrow, default, deserialize_with = "explicit_option")]
+    gradient: Option<Option<Gradient<'a>>>,
+    #[serde(borrow, default, deserialize_with = "explicit_option")]
+    border: Option<Option<Border<'a>>>,
+    #[serde(borrow, default, deserialize_with = "explicit_option")]
+    separator: Option<Option<Separator<'a>>>,
+    ghost: Option<bool>,
+    #[serde(borrow)]
+    id_prefix: Option<IdPrefixDef<'a>>,
+    text_rendering: Option<TextRendering>,
+    font_weight: Option<FontWeight>,
+    font_style: Option<FontStyle>,
+    font_family: Option<FontFamily>,
+}
+
+/// An ID prefix as deserialized, where a custom prefix may not borrow from
+/// the input.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum IdPrefixDef<'a> {
+    None,
+    Custom(#[serde(borrow)] Cow<'a, str>),
+    Hash,
+}
+
+/// Checks a custom ID prefix.
+fn custom_prefix<E>(prefix: &str) -> Result<IdPrefix<'_>, E>
+where
+    E: de::Error,
+{
+    IdPrefix::custom(prefix)
+        .ok_or_else(|| E::invalid_value(de::Unexpected::Str(prefix), &"an XML name without colons"))
+}
+
+/// Distinguishes an explicit `null`, which unsets the value of the base
+/// style, from a missing field.
+fn explicit_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+impl<'a> StyleDef<'a> {
+    /// Applies the fields over the base style, given the ID prefix, which is
+    /// checked separately as a custom prefix may be owned.
+    fn into_style<'b, E>(self, id_prefix: Option<IdPrefix<'b>>) -> Result<Style<'b>, E>
+    where
+        'a: 'b,
+        E: de::Error,
+    {
+        let base = match self.base {
+            None | Some(StyleBase::Classic) => Style::classic(),
+            Some(StyleBase::Flat) => Style::flat(),
+            Some(StyleBase::Ghost) => Style::ghost(),
+            #[cfg(feature = "font-dejavu-sans")]
+            Some(StyleBase::Shields) => Style::shields(),
+            #[cfg(not(feature = "font-dejavu-sans"))]
+            Some(StyleBase::Shields) => {
+                return Err(E::custom(
+                    "the `shields` style requires the `font-dejavu-sans` feature",
+                ))
+            }
+        };
+        Ok(Style {
+            height: self.height.unwrap_or(base.height),
+            border_radius: self.border_radius.unwrap_or(base.border_radius),
+            background: self.background.unwrap_or(base.background),
+            text_color: self.text_color.unwrap_or(base.text_color),
+            text_spacing: self.text_spacing.unwrap_or(base.text_spacing),
+            text_shadow_color: self.text_shadow_color.unwrap_or(base.text_shadow_color),
+            text_shadow_opacity: self.text_shadow_opacity.unwrap_or(base.text_shadow_opacity),
+            text_shadow_offset: self.text_shadow_offset.unwrap_or(base.text_shadow_offset),
+            label_background: self.label_background.unwrap_or(base.label_background),
+            label_text_color: self.label_text_color.unwrap_or(base.label_text_color),
+            gradient: self.gradient.unwrap_or(base.gradient),
+            border: self.border.unwrap_or(base.border),
+            separator: self.separator.unwrap_or(base.separator),
+            ghost: self.ghost.unwrap_or(base.ghost),
+            id_prefix: id_prefix.unwrap_or(base.id_prefix),
+            text_rendering: self.text_rendering.unwrap_or(base.text_rendering),
+            font_weight: self.font_weight.unwrap_or(base.font_weight),
+            font_style: self.font_style.unwrap_or(base.font_style),
+            font_family: self.font_family.unwrap_or(base.font_family),
+        })
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for Style<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut def = StyleDef::deserialize(deserializer)?;
+        let id_prefix =
+            match def.id_prefix.take() {
+                None => None,
+                Some(IdPrefixDef::None) => Some(IdPrefix::None),
+                Some(IdPrefixDef::Hash) => Some(IdPrefix::Hash),
+                Some(IdPrefixDef::Custom(Cow::Borrowed(prefix))) => Some(custom_prefix(prefix)?),
+                // A style can't own a prefix, such as one with escapes.
+                Some(IdPrefixDef::Custom(Cow::Owned(_))) => return Err(de::Error::custom(
+                    "a custom ID prefix must be borrowed from the input, use a `StyleBuf` instead",
+                )),
+            };
+        def.into_style(id_prefix)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl<'a> Serialize for CustomPrefix<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for CustomPrefix<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct CustomPrefixVisitor;
+
+        impl<'de> Visitor<'de> for CustomPrefixVisitor {
+            type Value = CustomPrefix<'de>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an XML name without colons")
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                CustomPrefix::new(v).ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_str(CustomPrefixVisitor)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl Serialize for StyleBuf {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_style().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for StyleBuf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut def = StyleDef::deserialize(deserializer)?;
+        // Custom prefixes are copied into the owned style, so they needn't
+        // borrow from the input.
+        let prefix = def.id_prefix.take();
+        let id_prefix = match prefix {
+            None => None,
+            Some(IdPrefixDef::None) => Some(IdPrefix::None),
+            Some(IdPrefixDef::Hash) => Some(IdPrefix::Hash),
+            Some(IdPrefixDef::Custom(ref prefix)) => Some(custom_prefix(prefix)?),
+        };
+        def.into_style(id_prefix)?
+            .into_owned()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GradientDirection, GradientShape, GradientTarget};
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_style_base() {
+        let json = r#"{
+            "base": "flat",
+            "background": "orange",
+            "textColor": "333",
+            "textShadowOpacity": ".2",
+            "labelBackground": null
+        }"#;
+        let style: Style<'_> = serde_json::from_str(json).unwrap();
+        assert_eq!(style.background, Color::Orange);
+        assert_eq!(style.text_color, Color::Custom("333"));
+        assert_eq!(style.text_shadow_opacity, Opacity::raw(".2"));
+        assert_eq!(style.label_background, None);
+        assert_eq!(style.border_radius, Style::flat().border_radius);
+        assert!(style.gradient.is_none());
+    }
+
+    #[test]
+    fn test_shields_base() {
+        let style = serde_json::from_str::<Style<'_>>(r#"{"base": "shields"}"#);
+        #[cfg(feature = "font-dejavu-sans")]
+        assert_eq!(style.unwrap().font_family, FontFamily::DejaVuSans);
+        #[cfg(not(feature = "font-dejavu-sans"))]
+        assert!(style
+            .unwrap_err()
+            .to_string()
+            .contains("the `shields` style requires the `font-dejavu-sans` feature"));
+
+        // The family is known without its font.
+        let style: Style<'_> = serde_json::from_str(r#"{"fontFamily": "dejaVuSans"}"#).unwrap();
+        assert_eq!(style.font_family, FontFamily::DejaVuSans);
+    }
+
+    #[test]
+    fn test_style_roundtrip() {
+        let json = serde_json::to_string(&Style::classic()).unwrap();
+        let style: Style<'_> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&style).unwrap(), json);
+        assert_eq!(style.gradient.unwrap().stops[0].opacity, Opacity::raw(".1"));
+    }
+
+    #[test]
+    fn test_gradient() {
+        let json = r#"{"gradient": {
+            "stops": [{"offset": 0, "color": "fff", "opacity": ".3"}, {"offset": 1, "opacity": 0}],
+            "shape": {"linear": "toRight"},
+            "target": "segments"
+        }}"#;
+        let gradient = serde_json::from_str::<Style<'_>>(json)
+            .unwrap()
+            .gradient
+            .unwrap();
+        assert_eq!(gradient.stops.len(), 2);
+        assert_eq!(gradient.stops[1].color, None);
+        assert_eq!(
+            gradient.shape,
+            GradientShape::Linear(GradientDirection::ToRight)
+        );
+        assert_eq!(gradient.target, GradientTarget::Segments);
+
+        let json = r#"{"gradient": {"stops": [], "shape": "radial"}}"#;
+        let gradient = serde_json::from_str::<Style<'_>>(json)
+            .unwrap()
+            .gradient
+            .unwrap();
+        assert_eq!(gradient.shape, GradientShape::Radial);
+        assert_eq!(gradient.target, GradientTarget::Badge);
+    }
+
+    #[test]
+    fn test_border_separator() {
+        let json = r#"{
+            "border": {"color": "blue", "width": 1},
+            "separator": {"type": "chevron", "width": 4}
+        }"#;
+        let style: Style<'_> = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            style.border,
+            Some(Border {
+                color: Color::Blue,
+                width: 1
+            })
+        );
+        assert_eq!(style.separator, Some(Separator::Chevron { width: 4 }));
+    }
+
+    #[test]
+    fn test_style_invalid() {
+        let invalid = [
+            r#"{"background": "nope"}"#,
+            r#"{"textShadowOpacity": "2"}"#,
+            r#"{"base": "plastic"}"#,
+            r#"{"unknown": 1}"#,
+        ];
+        for json in invalid.iter() {
+            assert!(serde_json::from_str::<Style<'_>>(json).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn test_owned_input() {
+        // Escaped strings can't be borrowed, so custom values become owned.
+        let json = r#"{
+            "background": "r\u0065d",
+            "textColor": "f\u0066f",
+            "textShadowOpacity": "\u002e5",
+            "gradient": {"stops": [{"offset": 0, "color": "eee", "opacity": 0.5}]}
+        }"#;
+        let style: Style<'_> = serde_json::from_str(json).unwrap();
+        assert_eq!(style.background, Color::Red);
+        assert_eq!(style.text_color, Color::Rgb(255, 255, 255));
+        assert_eq!(style.text_shadow_opacity.to_string(), ".5");
+        assert_eq!(style.gradient.unwrap().stops[0].opacity.to_string(), ".5");
+        assert!(serde_json::from_str::<Style<'_>>(r#"{"background": "z\u007az"}"#).is_err());
+    }
+
+    #[test]
+    fn test_id_prefix() {
+        let style: Style<'_> = serde_json::from_str(r#"{"idPrefix": {"custom": "a-"}}"#).unwrap();
+        assert_eq!(style.id_prefix, IdPrefix::custom("a-").unwrap());
+        let json = r#"{"idPrefix": {"custom": "a></g><script>"}}"#;
+        assert!(serde_json::from_str::<Style<'_>>(json).is_err());
+        let style: Style<'_> = serde_json::from_str(r#"{"idPrefix": "hash"}"#).unwrap();
+        assert_eq!(style.id_prefix, IdPrefix::Hash);
+    }
+
+    #[test]
+    fn test_style_buf_id_prefix() {
+        // Strings read from TOML and readers are owned.
+        let style: StyleBuf = toml::from_str(r#"idPrefix = { custom = "build-" }"#).unwrap();
+        assert_eq!(
+            style.as_style().id_prefix,
+            IdPrefix::custom("build-").unwrap()
+        );
+        let json = r#"{"idPrefix": {"custom": "build-"}}"#;
+        let style: StyleBuf = serde_json::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(
+            style.as_style().id_prefix,
+            IdPrefix::custom("build-").unwrap()
+        );
+
+        assert!(toml::from_str::<StyleBuf>(r#"idPrefix = { custom = "a>" }"#).is_err());
+        let json = r#"{"idPrefix": {"custom": "\u0061-"}}"#;
+        assert!(serde_json::from_str::<Style<'_>>(json).is_err());
+        let style: StyleBuf = serde_json::from_str(json).unwrap();
+        assert_eq!(style.as_style().id_prefix, IdPrefix::custom("a-").unwrap());
+    }
+
+    #[test]
+    fn test_serialize_owned() {
+        let json = serde_json::to_string(&Style::classic().into_owned().unwrap()).unwrap();
+        assert!(json.contains(r#""labelBackground":"555555""#), "{}", json);
+        assert!(json.contains(r#""textShadowOpacity":".25""#), "{}", json);
+
+        let style: StyleBuf = serde_json::from_str(&json).unwrap();
+        assert_eq!(style.as_style().background, Color::Blue);
+    }
+}
@@ -0,0 +1,191 @@
+//! Sprite sheets of many badges sharing glyph definitions.
+//!
+//! A sprite sheet is a single SVG with a `<symbol>` per badge. Each unique
+//! glyph is defined once for the whole sheet and placed with a `<use>`, rather
+//! than repeating a path of the text in every badge.
+//!
+//! ```rust
+//! let style = badgen::Style::classic();
+//! let ttf_font = badgen::style_font(&style);
+//! let mut font = badgen::font_for_style(&ttf_font, &style);
+//!
+//! let mut writer = badgen::sprite::SpriteWriter::new(String::new(), &mut font).unwrap();
+//!
+//! writer
+//!     .write_badge("build", &style, "passing", Some("build"))
+//!     .unwrap()
+//!     .write_badge("license", &style, "MIT", Some("license"))
+//!     .unwrap();
+//!
+//! let sprite = writer.finish().unwrap();
+//!
+//! println!("{}", sprite);
+//! ```
+//!
+//! Each badge can then be shown with its symbol ID, sized by the badge's
+//! `width` and `height`:
+//!
+//! ```html
+//! <svg width="90" height="20"><use href="sprite.svg#build"/></svg>
+//! ```
+//!
+//! Element IDs within a symbol are prefixed with the symbol ID and a dash, and
+//! glyph IDs start with `_`, so symbol IDs should be unique and not start
+//! with `_`. The [`id_prefix`](crate::Style::id_prefix) of a style is not
+//! used.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::svg::SvgWrite;
+use crate::util::Escape;
+use crate::{
+    render_badge_glyphs, write_badge_body, write_glyph_paths, write_size, BadgeText, Font,
+    GlyphSet, GlyphUse, Layout, Prefix, Style,
+};
+
+/// The prefix of the glyph IDs shared by all symbols.
+const GLYPH_PREFIX: Prefix<'static> = Prefix::Custom("_");
+
+/// Writes badges as symbols of an SVG sprite sheet to a [`fmt::Write`].
+///
+/// The sprite sheet is only complete once [`finish`](Self::finish) is called.
+pub struct SpriteWriter<'f, W, F> {
+    svg: SvgWrite<W>,
+    font: &'f mut F,
+    glyphs: GlyphSet,
+    label_uses: Vec<GlyphUse>,
+    status_uses: Vec<GlyphUse>,
+}
+
+impl<'f, W, F> SpriteWriter<'f, W, F>
+where
+    W: fmt::Write,
+    F: Font,
+{
+    /// Construct a new [`SpriteWriter`], starting the sprite sheet.
+    pub fn new(w: W, font: &'f mut F) -> Result<Self, fmt::Error> {
+        let mut svg = SvgWrite::start(w)?;
+        svg.attr_str("xmlns", "http://www.w3.org/2000/svg")?;
+        Ok(Self {
+            svg,
+            font,
+            glyphs: GlyphSet::default(),
+            label_uses: Vec::new(),
+            status_uses: Vec::new(),
+        })
+    }
+
+    /// Writes a badge as a symbol given an ID, style, status and optional
+    /// label.
+    pub fn write_badge<S>(
+        &mut self,
+        id: &str,
+        style: &Style<'_>,
+        status: S,
+        label: Option<&str>,
+    ) -> Result<&mut Self, fmt::Error>
+    where
+        S: fmt::Display,
+    {
+        let (label_width, status_width) = render_badge_glyphs(
+            style,
+            &status,
+            label,
+            self.font,
+            &mut self.glyphs,
+            &mut self.label_uses,
+            &mut self.status_uses,
+        )?;
+        let layout = Layout::new(style, label_width, status_width);
+        let text = BadgeText::Glyphs {
+            label: Some(&self.label_uses[..]).filter(|_| label_width.is_some()),
+            status: &self.status_uses,
+            paths: None,
+            glyph_prefix: GLYPH_PREFIX,
+        };
+
+        self.svg
+            .open("symbol")?
+            .attr_fn("id", |w| Escape(id).fmt(w))?;
+        write_size(&mut self.svg, &layout)?;
+
+        let label = label.as_ref().map(|l| l as &dyn fmt::Display);
+        write_badge_body(
+            &mut self.svg,
+            style,
+            &layout,
+            &text,
+            Prefix::Symbol(id),
+            label,
+            &status,
+        )?;
+
+        self.svg.close("symbol")?;
+        Ok(self)
+    }
+
+    /// Writes the shared glyph definitions and finishes the sprite sheet,
+    /// returning the underlying writer.
+    pub fn finish(mut self) -> Result<W, fmt::Error> {
+        if !self.glyphs.is_empty() {
+            self.svg.open("defs")?;
+            write_glyph_paths(&mut self.svg, &self.glyphs, GLYPH_PREFIX)?;
+            self.svg.close("defs")?;
+        }
+        self.svg.finish()
+    }
+}
+
+impl<'f, W, F> fmt::Debug for SpriteWriter<'f, W, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpriteWriter")
+            .field("glyphs", &self.glyphs)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "font-noto-sans"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    fn sprite(badges: &[(&str, &str, Option<&str>)]) -> String {
+        let ttf_font = crate::style_font(&Style::classic());
+        let mut font = crate::font_for_style(&ttf_font, &Style::classic());
+        let mut writer = SpriteWriter::new(String::new(), &mut font).unwrap();
+        for (id, status, label) in badges {
+            writer
+                .write_badge(id, &Style::classic(), status, *label)
+                .unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn test_sprite_symbols() {
+        let svg = sprite(&[("build", "passing", Some("build")), ("lic", "MIT", None)]);
+        assert_eq!(svg.matches("<symbol").count(), 2);
+        assert!(svg.contains(r#"<symbol id="build" width=""#));
+        assert!(svg.contains(r#"id="build-s""#));
+        assert!(svg.contains(r#"id="build-l""#));
+        assert!(svg.contains(r##"href="#lic-s""##));
+        assert!(!svg.contains(r#"id="lic-l""#));
+        assert!(svg.contains(r##"mask="url(#build-m)""##));
+
+        // The badge size matches a standalone badge.
+        let badge = crate::badge(&Style::classic(), "passing", Some("build")).unwrap();
+        let size = &badge[..badge.find(" xmlns").unwrap()];
+        assert!(svg.contains(&size["<svg".len()..]));
+    }
+
+    #[test]
+    fn test_sprite_shared_glyphs() {
+        // "passing" and "missing" share all glyphs except "p", "a" and "m".
+        let svg = sprite(&[("a", "passing", None), ("b", "missing", None)]);
+        assert_eq!(svg.matches(r#"<path id="_c"#).count(), 7);
+        assert_eq!(svg.matches(r##"href="#_c"##).count(), 14);
+        assert!(svg.contains(r#"id="_c6""#));
+        assert!(!svg.contains(r#"id="_c7""#));
+    }
+}
@@ -1,4 +1,11 @@
-use core::fmt;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::{self, Write};
+use core::ops::{Deref, Mul};
+
+use crate::util::write_number;
+use crate::Point;
 
 pub(crate) const GREEN_COLOR_HEX: &str = "3C1";
 pub(crate) const BLUE_COLOR_HEX: &str = "08C";
@@ -11,8 +18,30 @@ pub(crate) const GREY_COLOR_HEX: &str = "999";
 pub(crate) const CYAN_COLOR_HEX: &str = "1BC";
 pub(crate) const BLACK_COLOR_HEX: &str = "2A2A2A";
 
+const CLASSIC_GRADIENT_STOPS: &[GradientStop<'static>] = &[
+    GradientStop {
+        offset: 0.0,
+        color: Some(Color::Custom("eee")),
+        opacity: Opacity::raw(".1"),
+    },
+    GradientStop {
+        offset: 1.0,
+        color: None,
+        opacity: Opacity::raw(".1"),
+    },
+];
+
 /// A badge style.
+///
+/// With the `serde` feature, a style may be deserialized with a `base` preset
+/// name (`classic`, `flat` or `ghost`) which the remaining fields are applied
+/// on top of.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
 #[non_exhaustive]
 pub struct Style<'a> {
     /// The height of the badge.
@@ -46,31 +75,75 @@ pub struct Style<'a> {
     // pub icon_width: u16,
     /// The background gradient of the badge.
     pub gradient: Option<Gradient<'a>>,
+    /// The border around the badge.
+    pub border: Option<Border<'a>>,
+    /// The separator between the label and status.
+    pub separator: Option<Separator<'a>>,
+    /// Whether the badge is a ghost badge, with a transparent background.
+    ///
+    /// The `background` color is used for the border and status text instead,
+    /// with the `label_background` color used for the label text. The border
+    /// width is taken from `border` if set.
+    pub ghost: bool,
+    /// The prefix of element IDs within the SVG badge.
+    pub id_prefix: IdPrefix<'a>,
+    /// How text is rendered within the SVG badge.
+    pub text_rendering: TextRendering,
+    /// The weight of the text.
+    ///
+    /// This selects the face used by functions with the default font, and
+    /// has no effect when a font is given.
+    pub font_weight: FontWeight,
+    /// The style of the text.
+    ///
+    /// This selects the face used by functions with the default font, and
+    /// has no effect when a font is given.
+    pub font_style: FontStyle,
+    /// The font family of the text.
+    ///
+    /// This selects the font used by functions with the default font, and
+    /// has no effect when a font is given.
+    pub font_family: FontFamily,
 }
 
+/// The classic gradient, as a constant so presets can be built from
+/// [`CLASSIC`] with struct update syntax.
+const CLASSIC_GRADIENT: Gradient<'static> = Gradient {
+    stops: GradientStops::Borrowed(CLASSIC_GRADIENT_STOPS),
+    shape: GradientShape::Linear(GradientDirection::ToBottom),
+    target: GradientTarget::Badge,
+};
+
+/// The classic style, which the other presets are derived from.
+const CLASSIC: Style<'static> = Style {
+    height: 20,
+    border_radius: 3,
+    background: Color::Blue,
+    // text_overlay: false,
+    text_color: Color::Custom("fff"),
+    text_spacing: 0.8,
+    text_shadow_color: Color::Custom("000"),
+    text_shadow_opacity: Opacity::raw(".25"),
+    text_shadow_offset: 1,
+    label_background: Some(Color::Custom("555")),
+    label_text_color: None,
+    // icon_path: None,
+    // icon_width: 13,
+    gradient: Some(CLASSIC_GRADIENT),
+    border: None,
+    separator: None,
+    ghost: false,
+    id_prefix: IdPrefix::None,
+    text_rendering: TextRendering::Paths,
+    font_weight: FontWeight::Normal,
+    font_style: FontStyle::Normal,
+    font_family: FontFamily::NotoSans,
+};
+
 impl<'a> Style<'a> {
     /// A classic badge style.
     pub const fn classic() -> Self {
-        Self {
-            height: 20,
-            border_radius: 3,
-            background: Color::Blue,
-            // text_overlay: false,
-            text_color: Color::Custom("fff"),
-            text_spacing: 0.8,
-            text_shadow_color: Color::Custom("000"),
-            text_shadow_opacity: Opacity::raw(".25"),
-            text_shadow_offset: 1,
-            label_background: Some(Color::Custom("555")),
-            label_text_color: None,
-            // icon_path: None,
-            // icon_width: 13,
-            gradient: Some(Gradient {
-                start: Color::Custom("eee"),
-                end: None,
-                opacity: Opacity::raw(".1"),
-            }),
-        }
+        CLASSIC
     }
 
     /// A flat badge style.
@@ -79,11 +152,161 @@ impl<'a> Style<'a> {
             gradient: None,
             border_radius: 0,
             text_shadow_opacity: Opacity::raw(".1"),
-            ..Self::classic()
+            ..CLASSIC
+        }
+    }
+
+    /// A ghost badge style, with a transparent background and colored border
+    /// and text.
+    pub const fn ghost() -> Self {
+        Self {
+            gradient: None,
+            text_shadow_opacity: Opacity::raw("0"),
+            ghost: true,
+            ..CLASSIC
+        }
+    }
+
+    /// A classic badge style using DejaVu Sans without letter spacing, which
+    /// lines up with shields.io badges to within a pixel or two.
+    ///
+    /// Available with the `font-dejavu-sans` feature.
+    #[cfg(feature = "font-dejavu-sans")]
+    pub const fn shields() -> Self {
+        Self {
+            text_spacing: 0.0,
+            font_family: FontFamily::DejaVuSans,
+            ..CLASSIC
+        }
+    }
+
+    /// Converts the style into a [`StyleBuf`] which no longer borrows.
+    ///
+    /// Custom colors are converted to [`Color::Rgb`] and opacities to their
+    /// numeric values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a custom color or raw opacity is invalid, rather
+    /// than guessing at a value.
+    pub fn into_owned(self) -> Result<StyleBuf, IntoOwnedError> {
+        // Custom prefixes are held separately as the style can't own them.
+        let (id_prefix, custom_prefix) = match self.id_prefix {
+            IdPrefix::None => (IdPrefix::None, None),
+            IdPrefix::Custom(prefix) => (IdPrefix::None, Some(String::from(prefix.as_str()))),
+            IdPrefix::Hash => (IdPrefix::Hash, None),
+        };
+        let style = Style {
+            height: self.height,
+            border_radius: self.border_radius,
+            background: self.background.into_owned()?,
+            text_color: self.text_color.into_owned()?,
+            text_spacing: self.text_spacing,
+            text_shadow_color: self.text_shadow_color.into_owned()?,
+            text_shadow_opacity: self.text_shadow_opacity.into_owned()?,
+            text_shadow_offset: self.text_shadow_offset,
+            label_background: self.label_background.map(Color::into_owned).transpose()?,
+            label_text_color: self.label_text_color.map(Color::into_owned).transpose()?,
+            gradient: self.gradient.map(Gradient::into_owned).transpose()?,
+            border: self.border.map(Border::into_owned).transpose()?,
+            separator: self.separator.map(Separator::into_owned).transpose()?,
+            ghost: self.ghost,
+            id_prefix,
+            text_rendering: self.text_rendering,
+            font_weight: self.font_weight,
+            font_style: self.font_style,
+            font_family: self.font_family,
+        };
+        Ok(StyleBuf {
+            style,
+            custom_prefix,
+        })
+    }
+}
+
+/// A badge style which doesn't borrow, for styles constructed at runtime.
+///
+/// Create one with [`Style::into_owned`], and borrow it as a [`Style`] with
+/// [`StyleBuf::as_style`] to render badges.
+///
+/// # Example
+///
+/// ```rust
+/// use badgen::{Color, Style, StyleBuf};
+///
+/// let color = String::from("4c1");
+/// let mut style = Style::flat();
+/// style.background = Color::parse(&color).unwrap();
+///
+/// let style: StyleBuf = style.into_owned().unwrap();
+/// drop(color);
+///
+/// assert_eq!(style.as_style().background, Color::Rgb(0x44, 0xcc, 0x11));
+/// let badge = badgen::badge(&style.as_style(), "passing", Some("build")).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct StyleBuf {
+    style: Style<'static>,
+    /// The custom ID prefix, which the borrowed style can't hold.
+    custom_prefix: Option<String>,
+}
+
+impl StyleBuf {
+    /// Borrows the owned style as a [`Style`].
+    ///
+    /// This doesn't allocate, as gradient stops are borrowed.
+    pub fn as_style(&self) -> Style<'_> {
+        let style = &self.style;
+        Style {
+            height: style.height,
+            border_radius: style.border_radius,
+            background: style.background,
+            text_color: style.text_color,
+            text_spacing: style.text_spacing,
+            text_shadow_color: style.text_shadow_color,
+            text_shadow_opacity: style.text_shadow_opacity,
+            text_shadow_offset: style.text_shadow_offset,
+            label_background: style.label_background,
+            label_text_color: style.label_text_color,
+            gradient: style.gradient.as_ref().map(|gradient| Gradient {
+                stops: GradientStops::Borrowed(&gradient.stops),
+                shape: gradient.shape,
+                target: gradient.target,
+            }),
+            border: style.border,
+            separator: style.separator,
+            ghost: style.ghost,
+            id_prefix: match self.custom_prefix {
+                Some(ref prefix) => IdPrefix::Custom(CustomPrefix(prefix)),
+                None => style.id_prefix,
+            },
+            text_rendering: style.text_rendering,
+            font_weight: style.font_weight,
+            font_style: style.font_style,
+            font_family: style.font_family,
         }
     }
 }
 
+/// An error converting a borrowed value into one which no longer borrows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IntoOwnedError {
+    /// A custom color is not a valid RGB hex value.
+    InvalidColor,
+    /// A raw opacity is not a number between `0` and `1`.
+    InvalidOpacity,
+}
+
+impl fmt::Display for IntoOwnedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::InvalidColor => "invalid custom color",
+            Self::InvalidOpacity => "invalid opacity",
+        })
+    }
+}
+
 #[inline]
 fn is_valid_hex_color(hex: &str) -> bool {
     let len = hex.len();
@@ -119,6 +342,8 @@ pub enum Color<'a> {
     Black,
     /// A custom hex color in the form `RGB` or `RRGGBB`.
     Custom(&'a str),
+    /// A custom color given its red, green and blue components.
+    Rgb(u8, u8, u8),
 }
 
 impl<'a> Color<'a> {
@@ -127,6 +352,16 @@ impl<'a> Color<'a> {
     /// This can be either a RGB hex value, or a named color.
     #[inline]
     pub fn parse(s: &'a str) -> Option<Self> {
+        match Self::parse_named(s) {
+            Some(color) => Some(color),
+            None if is_valid_hex_color(s) => Some(Self::Custom(s)),
+            None => None,
+        }
+    }
+
+    /// Parses a named color value.
+    #[inline]
+    pub(crate) fn parse_named(s: &str) -> Option<Self> {
         let color = match s {
             "green" | "GREEN" => Self::Green,
             "blue" | "BLUE" => Self::Blue,
@@ -138,16 +373,64 @@ impl<'a> Color<'a> {
             "grey" | "GREY" | "gray" | "GRAY" => Self::Grey,
             "cyan" | "CYAN" => Self::Cyan,
             "black" | "BLACK" => Self::Black,
-            other if is_valid_hex_color(other) => Self::Custom(other),
             _ => return None,
         };
         Some(color)
     }
 
+    /// Returns the name of the color, or the RGB hex value if custom.
+    ///
+    /// Returns `None` for [`Color::Rgb`].
+    #[cfg(feature = "serde")]
+    pub(crate) fn name(&self) -> Option<&'a str> {
+        let name = match self {
+            Self::Green => "green",
+            Self::Blue => "blue",
+            Self::Red => "red",
+            Self::Yellow => "yellow",
+            Self::Orange => "orange",
+            Self::Purple => "purple",
+            Self::Pink => "pink",
+            Self::Grey => "grey",
+            Self::Cyan => "cyan",
+            Self::Black => "black",
+            Self::Custom(s) => s,
+            Self::Rgb(..) => return None,
+        };
+        Some(name)
+    }
+
+    /// Parses a color value, additionally accepting the named colors of
+    /// [shields.io](https://shields.io) and a leading `#` on hex values.
+    pub fn parse_shields(s: &'a str) -> Option<Self> {
+        let color = match s {
+            "brightgreen" | "success" => Self::Green,
+            "important" => Self::Orange,
+            "critical" => Self::Red,
+            "informational" => Self::Blue,
+            "lightgrey" | "lightgray" | "inactive" => Self::Grey,
+            "yellowgreen" => Self::Custom("a4a61d"),
+            "blueviolet" => Self::Custom("8a2be2"),
+            other => return Self::parse(other.strip_prefix('#').unwrap_or(other)),
+        };
+        Some(color)
+    }
+
     /// Returns a RGB hex string for the color.
+    ///
+    /// A [`Color::Rgb`] has no string to borrow and returns an empty string.
+    #[deprecated(note = "use `hex_str` or the `Display` implementation, which handle `Color::Rgb`")]
     #[inline]
-    pub fn as_str(&'a self) -> &str {
-        match self {
+    pub fn as_str(&self) -> &'a str {
+        self.hex_str().unwrap_or_default()
+    }
+
+    /// Returns a RGB hex string for the color, or `None` for [`Color::Rgb`].
+    ///
+    /// Use the [`fmt::Display`] implementation to write any color.
+    #[inline]
+    pub fn hex_str(&self) -> Option<&'a str> {
+        let hex = match self {
             Self::Green => GREEN_COLOR_HEX,
             Self::Blue => BLUE_COLOR_HEX,
             Self::Red => RED_COLOR_HEX,
@@ -159,9 +442,60 @@ impl<'a> Color<'a> {
             Self::Cyan => CYAN_COLOR_HEX,
             Self::Black => BLACK_COLOR_HEX,
             Self::Custom(s) => s,
+            Self::Rgb(..) => return None,
+        };
+        Some(hex)
+    }
+
+    /// Returns the red, green and blue components of the color.
+    ///
+    /// Returns `None` if a custom color is not a valid RGB hex value.
+    pub fn to_rgb(&self) -> Option<[u8; 3]> {
+        let hex = match (self, self.hex_str()) {
+            (Self::Rgb(r, g, b), _) => return Some([*r, *g, *b]),
+            (_, Some(hex)) => hex.as_bytes(),
+            (_, None) => return None,
+        };
+        let digit = |i: usize| (hex[i] as char).to_digit(16).map(|d| d as u8);
+        match hex.len() {
+            3 => Some([digit(0)? * 17, digit(1)? * 17, digit(2)? * 17]),
+            6 => Some([
+                digit(0)? << 4 | digit(1)?,
+                digit(2)? << 4 | digit(3)?,
+                digit(4)? << 4 | digit(5)?,
+            ]),
+            _ => None,
         }
     }
 
+    /// Converts the color into one which no longer borrows.
+    ///
+    /// Custom colors are converted to [`Color::Rgb`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IntoOwnedError::InvalidColor`] if a custom color is not a
+    /// valid RGB hex value.
+    pub fn into_owned(self) -> Result<Color<'static>, IntoOwnedError> {
+        let color = match self {
+            Self::Green => Color::Green,
+            Self::Blue => Color::Blue,
+            Self::Red => Color::Red,
+            Self::Yellow => Color::Yellow,
+            Self::Orange => Color::Orange,
+            Self::Purple => Color::Purple,
+            Self::Pink => Color::Pink,
+            Self::Grey => Color::Grey,
+            Self::Cyan => Color::Cyan,
+            Self::Black => Color::Black,
+            Self::Custom(_) | Self::Rgb(..) => {
+                let [r, g, b] = self.to_rgb().ok_or(IntoOwnedError::InvalidColor)?;
+                Color::Rgb(r, g, b)
+            }
+        };
+        Ok(color)
+    }
+
     /// Writes the color to a [`fmt::Write`].
     #[inline]
     pub fn fmt<W>(&self, mut w: W) -> fmt::Result
@@ -169,18 +503,73 @@ impl<'a> Color<'a> {
         W: fmt::Write,
     {
         w.write_char('#')?;
-        w.write_str(self.as_str())
+        write!(w, "{}", self)
     }
 }
 
-/// Wrapper around a string opacity value.
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Opacity<'a>(&'a str);
+/// Writes the RGB hex value of the color, without a leading `#`.
+impl<'a> fmt::Display for Color<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+        match self {
+            Self::Rgb(r, g, b) => {
+                for v in [r, g, b].iter() {
+                    f.write_char(HEX_DIGITS[(**v >> 4) as usize] as char)?;
+                    f.write_char(HEX_DIGITS[(**v & 0xf) as usize] as char)?;
+                }
+                Ok(())
+            }
+            _ => f.write_str(self.hex_str().unwrap_or_default()),
+        }
+    }
+}
+
+/// An opacity value, either a string or numeric.
+///
+/// Opacities compare by their numeric value, and can be multiplied or
+/// composited to derive new opacities.
+///
+/// # Example
+///
+/// ```rust
+/// use badgen::Opacity;
+///
+/// let stale = Opacity::from_f32(0.5).unwrap();
+/// let dimmed = Opacity::parse(".8").unwrap() * stale;
+///
+/// assert_eq!(dimmed.to_string(), ".4");
+/// assert!(dimmed < stale);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Opacity<'a>(OpacityValue<'a>);
+
+#[derive(Debug, Clone, Copy)]
+enum OpacityValue<'a> {
+    Str(&'a str),
+    Num(f32),
+}
 
 impl<'a> Opacity<'a> {
     /// A raw unchecked opacity value.
     pub const fn raw(s: &'a str) -> Self {
-        Self(s)
+        Self(OpacityValue::Str(s))
+    }
+
+    /// A numeric opacity value, assumed to be between `0` and `1`.
+    pub(crate) const fn num(v: f32) -> Self {
+        Self(OpacityValue::Num(v))
+    }
+
+    /// Construct a numeric opacity value.
+    ///
+    /// Returns `None` if the value is not between `0` and `1`.
+    pub fn from_f32(v: f32) -> Option<Self> {
+        if (0.0..=1.0).contains(&v) {
+            // Adding zero normalizes negative zero.
+            Some(Self::num(v + 0.0))
+        } else {
+            None
+        }
     }
 
     /// Parse an opacity value.
@@ -188,25 +577,25 @@ impl<'a> Opacity<'a> {
         let sb = s.as_bytes();
         match sb.len() {
             1 => match sb[0] {
-                b'1' | b'0' => Some(Self(s)),
+                b'1' | b'0' => Some(Self::raw(s)),
                 _ => None,
             },
             2 => match (sb[0], sb[1]) {
-                (b'.', b'0') => Some(Self("0")),
-                (b'.', c) if c.is_ascii_digit() => Some(Self(s)),
+                (b'.', b'0') => Some(Self::raw("0")),
+                (b'.', c) if c.is_ascii_digit() => Some(Self::raw(s)),
                 _ => None,
             },
             3 => match (sb[0], sb[1], sb[2]) {
-                (b'1', b'.', b'0') => Some(Self("1")),
-                (b'.', b'0', b'0') => Some(Self("0")),
-                (b'.', c, b'0') if c.is_ascii_digit() => Some(Self(&s[..2])),
-                (b'0', b'.', c) if c.is_ascii_digit() => Some(Self(&s[1..])),
-                (b'.', c1, c2) if c1.is_ascii_digit() && c2.is_ascii_digit() => Some(Self(s)),
+                (b'1', b'.', b'0') => Some(Self::raw("1")),
+                (b'.', b'0', b'0') => Some(Self::raw("0")),
+                (b'.', c, b'0') if c.is_ascii_digit() => Some(Self::raw(&s[..2])),
+                (b'0', b'.', c) if c.is_ascii_digit() => Some(Self::raw(&s[1..])),
+                (b'.', c1, c2) if c1.is_ascii_digit() && c2.is_ascii_digit() => Some(Self::raw(s)),
                 _ => None,
             },
             4 => match &s[..2] {
                 "0." => Self::parse(&s[1..]),
-                "1." if &s[2..] == "00" => Some(Self("1")),
+                "1." if &s[2..] == "00" => Some(Self::raw("1")),
                 _ => None,
             },
             _ => None,
@@ -216,19 +605,73 @@ impl<'a> Opacity<'a> {
     /// Returns `true` if the value is completely opaque.
     #[inline]
     pub fn is_opaque(&self) -> bool {
-        self.0 == "1"
+        match self.0 {
+            OpacityValue::Str(s) => s == "1",
+            OpacityValue::Num(v) => v >= 1.0,
+        }
     }
 
     /// Returns `true` if the value is completely transparent.
     #[inline]
     pub fn is_transparent(&self) -> bool {
-        self.0 == "0"
+        match self.0 {
+            OpacityValue::Str(s) => s == "0",
+            OpacityValue::Num(v) => v <= 0.0,
+        }
     }
 
-    /// Returns the opacity value.
+    /// Returns the string opacity value.
+    ///
+    /// A numeric opacity has no string to borrow and returns an empty string.
+    #[deprecated(note = "use the `Display` implementation, which handles numeric opacities")]
     #[inline]
-    pub fn as_str(&'a self) -> &str {
-        self.0
+    pub fn as_str(&self) -> &'a str {
+        match self.0 {
+            OpacityValue::Str(s) => s,
+            OpacityValue::Num(_) => "",
+        }
+    }
+
+    /// Returns the numeric opacity value.
+    ///
+    /// Returns `None` if a raw string value is not a valid number.
+    pub fn to_f32(&self) -> Option<f32> {
+        match self.0 {
+            OpacityValue::Str(s) => s.parse().ok(),
+            OpacityValue::Num(v) => Some(v),
+        }
+    }
+
+    /// Converts the opacity into one which no longer borrows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IntoOwnedError::InvalidOpacity`] if a raw string value is
+    /// not a number between `0` and `1`.
+    pub fn into_owned(self) -> Result<Opacity<'static>, IntoOwnedError> {
+        match self.to_f32() {
+            Some(v) if (0.0..=1.0).contains(&v) => Ok(Opacity::num(v)),
+            _ => Err(IntoOwnedError::InvalidOpacity),
+        }
+    }
+
+    /// Composites the opacity over another, as with stacked layers.
+    ///
+    /// Invalid raw string values are treated as opaque.
+    pub fn over(self, below: Opacity<'_>) -> Opacity<'static> {
+        let (a, b) = (self.value(), below.value());
+        Opacity::num(a + b * (1.0 - a))
+    }
+
+    /// Returns the numeric value, clamped between `0` and `1` and defaulting
+    /// to opaque if invalid.
+    #[inline]
+    fn value(&self) -> f32 {
+        match self.to_f32() {
+            Some(v) if v.is_nan() => 1.0,
+            Some(v) => v.clamp(0.0, 1.0),
+            None => 1.0,
+        }
     }
 
     /// Writes the opacity to a [`fmt::Write`].
@@ -237,40 +680,752 @@ impl<'a> Opacity<'a> {
     where
         W: fmt::Write,
     {
-        w.write_str(self.as_str())
+        write!(w, "{}", self)
+    }
+}
+
+impl<'a, 'b> PartialEq<Opacity<'b>> for Opacity<'a> {
+    fn eq(&self, other: &Opacity<'b>) -> bool {
+        match (self.0, other.0) {
+            (OpacityValue::Str(a), OpacityValue::Str(b)) if a == b => true,
+            _ => matches!((self.to_f32(), other.to_f32()), (Some(a), Some(b)) if a == b),
+        }
     }
 }
 
-/// A two color gradient value.
+/// Invalid raw string values are unordered.
+impl<'a, 'b> PartialOrd<Opacity<'b>> for Opacity<'a> {
+    fn partial_cmp(&self, other: &Opacity<'b>) -> Option<Ordering> {
+        self.to_f32()?.partial_cmp(&other.to_f32()?)
+    }
+}
+
+/// Multiplies two opacities, treating invalid raw string values as opaque.
+impl<'a, 'b> Mul<Opacity<'b>> for Opacity<'a> {
+    type Output = Opacity<'static>;
+
+    fn mul(self, rhs: Opacity<'b>) -> Self::Output {
+        Opacity::num(self.value() * rhs.value())
+    }
+}
+
+/// Writes the opacity value, without a leading zero.
+impl<'a> fmt::Display for Opacity<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            OpacityValue::Str(s) => f.write_str(s),
+            OpacityValue::Num(v) => write_number(f, v),
+        }
+    }
+}
+
+/// A gradient value.
+///
+/// For a simple two color gradient, use [`Gradient::linear`].
+///
+/// # Example
+///
+/// ```rust
+/// use badgen::{Color, Gradient, GradientDirection, GradientShape, GradientStop, Opacity};
+///
+/// let stops = [
+///     GradientStop {
+///         offset: 0.0,
+///         color: Some(Color::Custom("fff")),
+///         opacity: Opacity::raw(".3"),
+///     },
+///     GradientStop {
+///         offset: 0.5,
+///         color: Some(Color::Custom("fff")),
+///         opacity: Opacity::raw(".1"),
+///     },
+///     GradientStop {
+///         offset: 0.5,
+///         color: None,
+///         opacity: Opacity::raw("0"),
+///     },
+/// ];
+///
+/// let mut gradient = Gradient::new(&stops);
+/// gradient.shape = GradientShape::Linear(GradientDirection::ToBottomRight);
+///
+/// let mut style = badgen::Style::classic();
+/// style.gradient = Some(gradient);
+/// ```
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
 pub struct Gradient<'a> {
-    /// The start color.
-    pub start: Color<'a>,
-    /// The end color.
-    pub end: Option<Color<'a>>,
-    /// The opacity of the gradient.
+    /// The color stops of the gradient.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub stops: GradientStops<'a>,
+    /// The shape of the gradient.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shape: GradientShape,
+    /// The parts of the badge the gradient is applied to.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub target: GradientTarget,
+}
+
+impl<'a> Gradient<'a> {
+    /// Construct a new [`Gradient`] from top to bottom over the whole badge.
+    pub const fn new(stops: &'a [GradientStop<'a>]) -> Self {
+        Self {
+            stops: GradientStops::Borrowed(stops),
+            shape: GradientShape::Linear(GradientDirection::ToBottom),
+            target: GradientTarget::Badge,
+        }
+    }
+
+    /// Construct a new two color [`Gradient`] from top to bottom over the
+    /// whole badge, with a shared opacity.
+    ///
+    /// This replaces the `start`, `end` and `opacity` fields gradients had
+    /// before they supported multiple stops, so
+    /// `Gradient { start, end, opacity }` becomes
+    /// `Gradient::linear(start, end, opacity)`.
+    pub fn linear(start: Color<'a>, end: Option<Color<'a>>, opacity: Opacity<'a>) -> Self {
+        let stop = |offset, color| GradientStop {
+            offset,
+            color,
+            opacity,
+        };
+        Self {
+            stops: GradientStops::Owned(alloc::vec![stop(0.0, Some(start)), stop(1.0, end)]),
+            ..Self::new(&[])
+        }
+    }
+
+    /// Converts the gradient into one which no longer borrows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a stop has an invalid color or opacity.
+    pub fn into_owned(self) -> Result<Gradient<'static>, IntoOwnedError> {
+        Ok(Gradient {
+            stops: GradientStops::Owned(
+                self.stops
+                    .iter()
+                    .map(|s| s.into_owned())
+                    .collect::<Result<_, _>>()?,
+            ),
+            shape: self.shape,
+            target: self.target,
+        })
+    }
+}
+
+/// A color stop of a [`Gradient`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct GradientStop<'a> {
+    /// The offset of the stop, between `0` and `1`.
+    pub offset: f32,
+    /// The color of the stop.
+    ///
+    /// If `None`, the SVG default of black is used.
+    #[cfg_attr(feature = "serde", serde(borrow, default))]
+    pub color: Option<Color<'a>>,
+    /// The opacity of the stop.
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub opacity: Opacity<'a>,
 }
 
+impl<'a> GradientStop<'a> {
+    /// Converts the stop into one which no longer borrows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the color or opacity is invalid.
+    pub fn into_owned(self) -> Result<GradientStop<'static>, IntoOwnedError> {
+        Ok(GradientStop {
+            offset: self.offset,
+            color: self.color.map(Color::into_owned).transpose()?,
+            opacity: self.opacity.into_owned()?,
+        })
+    }
+}
+
+/// The color stops of a [`Gradient`], either borrowed or owned.
+#[derive(Debug, Clone)]
+pub enum GradientStops<'a> {
+    /// Borrowed stops.
+    Borrowed(&'a [GradientStop<'a>]),
+    /// Owned stops.
+    Owned(Vec<GradientStop<'a>>),
+}
+
+impl<'a> Deref for GradientStops<'a> {
+    type Target = [GradientStop<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Borrowed(stops) => stops,
+            Self::Owned(stops) => stops,
+        }
+    }
+}
+
+impl<'a> From<&'a [GradientStop<'a>]> for GradientStops<'a> {
+    fn from(stops: &'a [GradientStop<'a>]) -> Self {
+        Self::Borrowed(stops)
+    }
+}
+
+impl<'a> From<Vec<GradientStop<'a>>> for GradientStops<'a> {
+    fn from(stops: Vec<GradientStop<'a>>) -> Self {
+        Self::Owned(stops)
+    }
+}
+
+/// The shape of a [`Gradient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum GradientShape {
+    /// A linear gradient in a given direction.
+    Linear(GradientDirection),
+    /// A radial gradient from the center outwards.
+    Radial,
+}
+
+impl Default for GradientShape {
+    fn default() -> Self {
+        Self::Linear(GradientDirection::ToBottom)
+    }
+}
+
+/// The direction of a linear [`Gradient`].
+///
+/// Diagonal directions run corner to corner of the area the gradient is
+/// applied to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum GradientDirection {
+    /// Top to bottom.
+    ToBottom,
+    /// Bottom to top.
+    ToTop,
+    /// Left to right.
+    ToRight,
+    /// Right to left.
+    ToLeft,
+    /// Top left to bottom right.
+    ToBottomRight,
+    /// Top right to bottom left.
+    ToBottomLeft,
+    /// Bottom left to top right.
+    ToTopRight,
+    /// Bottom right to top left.
+    ToTopLeft,
+}
+
+impl GradientDirection {
+    /// Returns the start and end points of the direction as a percentage of
+    /// the area the gradient is applied to.
+    pub(crate) fn points(self) -> [Point<u8>; 2] {
+        let (x1, y1, x2, y2) = match self {
+            Self::ToBottom => (0, 0, 0, 100),
+            Self::ToTop => (0, 100, 0, 0),
+            Self::ToRight => (0, 0, 100, 0),
+            Self::ToLeft => (100, 0, 0, 0),
+            Self::ToBottomRight => (0, 0, 100, 100),
+            Self::ToBottomLeft => (100, 0, 0, 100),
+            Self::ToTopRight => (0, 100, 100, 0),
+            Self::ToTopLeft => (100, 100, 0, 0),
+        };
+        [Point { x: x1, y: y1 }, Point { x: x2, y: y2 }]
+    }
+}
+
+/// The parts of a badge a [`Gradient`] is applied to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum GradientTarget {
+    /// Once over the whole badge.
+    #[default]
+    Badge,
+    /// Separately over the label and status.
+    Segments,
+    /// Only over the label.
+    Label,
+    /// Only over the status.
+    Status,
+}
+
+/// The prefix of element IDs within an SVG badge.
+///
+/// SVG element IDs are global to the document a badge is inlined in, so
+/// several badges inlined into one HTML page each need a unique prefix.
+///
+/// # Example
+///
+/// ```rust
+/// let mut style = badgen::Style::classic();
+/// style.id_prefix = badgen::IdPrefix::custom("build-").unwrap();
+///
+/// let badge = badgen::badge(&style, "passing", Some("build")).unwrap();
+///
+/// assert!(badge.contains(r##"href="#build-s""##));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum IdPrefix<'a> {
+    /// No prefix.
+    #[default]
+    None,
+    /// A custom prefix.
+    Custom(#[cfg_attr(feature = "serde", serde(borrow))] CustomPrefix<'a>),
+    /// A prefix derived from a hash of the badge content and style.
+    Hash,
+}
+
+impl<'a> IdPrefix<'a> {
+    /// A custom prefix, or `None` if the prefix isn't a valid XML name
+    /// without colons.
+    ///
+    /// See [`CustomPrefix::new`].
+    pub fn custom(prefix: &'a str) -> Option<Self> {
+        CustomPrefix::new(prefix).map(Self::Custom)
+    }
+}
+
+/// A custom element ID prefix, checked to be a valid XML name without colons.
+///
+/// The prefix is written as is into `id` attributes, `href` attributes and
+/// `url(#...)` references, so characters which would need escaping in any of
+/// those are rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomPrefix<'a>(&'a str);
+
+impl<'a> CustomPrefix<'a> {
+    /// Checks a custom prefix, returning `None` if it isn't a valid XML name
+    /// without colons.
+    ///
+    /// The prefix must start with a letter or `_`, followed by letters,
+    /// digits, `-`, `.` or `_`.
+    pub fn new(prefix: &'a str) -> Option<Self> {
+        let mut chars = prefix.chars();
+        match chars.next() {
+            Some(c) if is_name_start_char(c) => {}
+            _ => return None,
+        }
+        if chars.all(is_name_char) {
+            Some(Self(prefix))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the prefix.
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+}
+
+/// Returns `true` if the character may start an XML name, excluding `:`.
+fn is_name_start_char(c: char) -> bool {
+    matches!(c,
+        'A'..='Z'
+        | '_'
+        | 'a'..='z'
+        | '\u{c0}'..='\u{d6}'
+        | '\u{d8}'..='\u{f6}'
+        | '\u{f8}'..='\u{2ff}'
+        | '\u{370}'..='\u{37d}'
+        | '\u{37f}'..='\u{1fff}'
+        | '\u{200c}'..='\u{200d}'
+        | '\u{2070}'..='\u{218f}'
+        | '\u{2c00}'..='\u{2fef}'
+        | '\u{3001}'..='\u{d7ff}'
+        | '\u{f900}'..='\u{fdcf}'
+        | '\u{fdf0}'..='\u{fffd}'
+        | '\u{10000}'..='\u{effff}'
+    )
+}
+
+/// Returns `true` if the character may be within an XML name, excluding `:`.
+fn is_name_char(c: char) -> bool {
+    is_name_start_char(c)
+        || matches!(c,
+            '-' | '.' | '0'..='9' | '\u{b7}' | '\u{300}'..='\u{36f}' | '\u{203f}'..='\u{2040}'
+        )
+}
+
+/// How text is rendered within an SVG badge.
+///
+/// # Example
+///
+/// ```rust
+/// let mut style = badgen::Style::classic();
+/// style.text_rendering = badgen::TextRendering::Glyphs;
+///
+/// let badge = badgen::badge(&style, "passing", Some("build")).unwrap();
+///
+/// assert!(badge.contains(r##"<use href="#c0""##));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum TextRendering {
+    /// A single path per text, with the outline of every character.
+    #[default]
+    Paths,
+    /// Each unique glyph is defined once and placed with a `<use>`.
+    ///
+    /// Each placement adds around 27 bytes and each glyph definition around
+    /// 20 bytes, so this is only smaller when the outlines of repeated glyphs
+    /// outweigh that. Sizes in bytes of classic badges with the default font:
+    ///
+    /// | Label          | Status       | Paths | Glyphs |
+    /// |----------------|--------------|-------|--------|
+    /// | `build`        | `passing`    | 3522  | 3528   |
+    /// | `license`      | `MIT`        | 2395  | 2538   |
+    /// | `version`      | `1.2.3`      | 3502  | 3787   |
+    /// | `coverage`     | `99%`        | 4451  | 3997   |
+    /// | `docs`         | `passing`    | 3627  | 3431   |
+    /// | `dependencies` | `up to date` | 5485  | 3962   |
+    ///
+    /// Short text, and text with few repeated characters, is usually larger.
+    /// Glyphs are also shared between the badges of a
+    /// [`SpriteWriter`](crate::sprite::SpriteWriter), where the savings are
+    /// greater. This allocates when rendering.
+    Glyphs,
+}
+
+/// The font family of badge text.
+///
+/// Families without a font included in binary are rendered with Noto Sans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[non_exhaustive]
+pub enum FontFamily {
+    /// Noto Sans, the default font.
+    #[default]
+    NotoSans,
+    /// DejaVu Sans, with metrics close to the Verdana used by shields.io.
+    ///
+    /// Included in binary with the `font-dejavu-sans` feature.
+    DejaVuSans,
+}
+
+/// The weight of badge text.
+///
+/// Bold text uses the bold face of the default font with the
+/// `font-noto-sans-bold` feature, and is synthesized otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// let mut style = badgen::Style::classic();
+/// style.font_weight = badgen::FontWeight::Bold;
+///
+/// let badge = badgen::badge(&style, "passing", Some("build")).unwrap();
+///
+/// println!("{}", badge);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum FontWeight {
+    /// A regular weight.
+    #[default]
+    Normal,
+    /// A bold weight.
+    Bold,
+}
+
+/// The style of badge text.
+///
+/// Italic text uses the italic face of the default font with the
+/// `font-noto-sans-italic` feature, and is synthesized by slanting it
+/// otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum FontStyle {
+    /// Upright text.
+    #[default]
+    Normal,
+    /// Italic text.
+    Italic,
+}
+
+/// A border around a badge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Border<'a> {
+    /// The color of the border.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub color: Color<'a>,
+    /// The width of the border.
+    ///
+    /// The border is drawn inside the edge of the badge.
+    pub width: u16,
+}
+
+impl<'a> Border<'a> {
+    /// Converts the border into one which no longer borrows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the color is invalid.
+    pub fn into_owned(self) -> Result<Border<'static>, IntoOwnedError> {
+        Ok(Border {
+            color: self.color.into_owned()?,
+            width: self.width,
+        })
+    }
+}
+
+/// A separator between the label and status of a badge.
+///
+/// Separators are only drawn when a badge has a label.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "camelCase", deny_unknown_fields)
+)]
+pub enum Separator<'a> {
+    /// A solid line.
+    Solid {
+        /// The color of the line.
+        #[cfg_attr(feature = "serde", serde(borrow))]
+        color: Color<'a>,
+        /// The width of the line.
+        width: u16,
+    },
+    /// A transparent gap.
+    Gap {
+        /// The width of the gap.
+        width: u16,
+    },
+    /// A chevron pointing from the label into the status, in the label
+    /// background color.
+    Chevron {
+        /// The width of the chevron.
+        width: u16,
+    },
+}
+
+impl<'a> Separator<'a> {
+    /// Returns the extra width the separator adds between the label and
+    /// status.
+    pub(crate) fn spacing(&self) -> u16 {
+        match *self {
+            Self::Solid { .. } => 0,
+            Self::Gap { width } | Self::Chevron { width } => width,
+        }
+    }
+
+    /// Converts the separator into one which no longer borrows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the color is invalid.
+    pub fn into_owned(self) -> Result<Separator<'static>, IntoOwnedError> {
+        Ok(match self {
+            Self::Solid { color, width } => Separator::Solid {
+                color: color.into_owned()?,
+                width,
+            },
+            Self::Gap { width } => Separator::Gap { width },
+            Self::Chevron { width } => Separator::Chevron { width },
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::{String, ToString};
+
+    #[test]
+    fn test_color_parse_shields() {
+        assert_eq!(Color::parse_shields("success"), Some(Color::Green));
+        assert_eq!(Color::parse_shields("red"), Some(Color::Red));
+        assert_eq!(
+            Color::parse_shields("#fe7d37"),
+            Some(Color::Custom("fe7d37"))
+        );
+        assert_eq!(Color::parse_shields("##fff"), None);
+        assert_eq!(Color::parse_shields("nope"), None);
+    }
+
+    #[test]
+    fn test_color_to_rgb() {
+        assert_eq!(Color::Blue.to_rgb(), Some([0x00, 0x88, 0xcc]));
+        assert_eq!(Color::Custom("0a1B2c").to_rgb(), Some([0x0a, 0x1b, 0x2c]));
+        assert_eq!(Color::Custom("zzz").to_rgb(), None);
+        assert_eq!(Color::Custom("ffff").to_rgb(), None);
+    }
+
+    #[test]
+    fn test_color_rgb() {
+        let color = Color::Rgb(0x0a, 0x1b, 0xff);
+        assert_eq!(color.hex_str(), None);
+        assert_eq!(color.to_rgb(), Some([0x0a, 0x1b, 0xff]));
+        assert_eq!(color.to_string(), "0a1bff");
+        assert_eq!(Color::Blue.to_string(), "08C");
+        assert_eq!(Color::Custom("fff").hex_str(), Some("fff"));
+        assert_eq!(
+            Color::Custom("fff").into_owned(),
+            Ok(Color::Rgb(255, 255, 255))
+        );
+        assert_eq!(
+            Color::Custom("zzz").into_owned(),
+            Err(IntoOwnedError::InvalidColor)
+        );
+        assert_eq!(Color::Red.into_owned(), Ok(Color::Red));
+    }
+
+    #[test]
+    fn test_opacity_num() {
+        assert_eq!(Opacity::num(0.25).to_string(), ".25");
+        assert_eq!(Opacity::num(1.0).to_string(), "1");
+        assert_eq!(Opacity::num(0.0).to_string(), "0");
+        assert!(Opacity::num(1.0).is_opaque());
+        assert!(Opacity::num(0.0).is_transparent());
+        #[allow(deprecated)]
+        let raw = Opacity::num(0.25).as_str();
+        assert_eq!(raw, "");
+        assert_eq!(Opacity::raw(".1").into_owned().unwrap(), Opacity::num(0.1));
+        assert!(Opacity::raw("x").into_owned().is_err());
+        assert!(Opacity::raw("2").into_owned().is_err());
+    }
+
+    #[test]
+    fn test_opacity_from_f32() {
+        assert_eq!(Opacity::from_f32(0.125).unwrap().to_string(), ".125");
+        assert_eq!(Opacity::from_f32(0.111).unwrap().to_string(), ".111");
+        assert_eq!(Opacity::from_f32(-0.0).unwrap().to_string(), "0");
+        assert_eq!(Opacity::from_f32(1.5), None);
+        assert_eq!(Opacity::from_f32(-0.1), None);
+        assert_eq!(Opacity::from_f32(f32::NAN), None);
+    }
+
+    #[test]
+    fn test_opacity_ops() {
+        let half = Opacity::from_f32(0.5).unwrap();
+        assert_eq!(Opacity::raw(".50"), half);
+        assert!(Opacity::raw(".25") < half);
+        assert!(Opacity::raw("1") > half);
+        assert_eq!(Opacity::raw("x").partial_cmp(&half), None);
+        assert_eq!((half * Opacity::raw(".5")).to_string(), ".25");
+        assert_eq!((half * Opacity::raw("x")).to_string(), ".5");
+        assert_eq!(half.over(half).to_string(), ".75");
+        assert!(Opacity::raw("0").over(half) == half);
+    }
+
+    #[test]
+    fn test_gradient_linear() {
+        let gradient = Gradient::linear(Color::Custom("eee"), None, Opacity::raw(".1"));
+        assert_eq!(&gradient.stops[..], CLASSIC_GRADIENT_STOPS);
+        assert_eq!(gradient.shape, CLASSIC_GRADIENT.shape);
+        assert_eq!(gradient.target, CLASSIC_GRADIENT.target);
+    }
+
+    #[test]
+    fn test_custom_prefix() {
+        for prefix in ["build-", "_a.b", "b1", "é-"].iter() {
+            assert_eq!(CustomPrefix::new(prefix).map(|p| p.as_str()), Some(*prefix));
+        }
+        for prefix in ["", "1a", "-a", "a:b", "a b", "a)", "x\"/><script>"].iter() {
+            assert_eq!(CustomPrefix::new(prefix), None, "{}", prefix);
+        }
+    }
+
+    #[test]
+    fn test_style_into_owned() {
+        let color = String::from("4c1");
+        let mut style = Style::classic();
+        style.background = Color::parse(&color).unwrap();
+        let owned: StyleBuf = style.into_owned().unwrap();
+        drop(color);
+        let style = owned.as_style();
+        assert_eq!(style.background, Color::Rgb(0x44, 0xcc, 0x11));
+        assert_eq!(style.label_background, Some(Color::Rgb(0x55, 0x55, 0x55)));
+        assert_eq!(style.text_shadow_opacity.to_string(), ".25");
+        assert_eq!(style.id_prefix, IdPrefix::None);
+        let stops = &style.gradient.unwrap().stops;
+        assert_eq!(stops[0].color, Some(Color::Rgb(0xee, 0xee, 0xee)));
+
+        let prefix = String::from("a-");
+        let mut style = Style::classic();
+        style.id_prefix = IdPrefix::custom(&prefix).unwrap();
+        let owned = style.into_owned().unwrap();
+        drop(prefix);
+        assert_eq!(owned.as_style().id_prefix, IdPrefix::custom("a-").unwrap());
+
+        let mut style = Style::classic();
+        style.text_color = Color::Custom("zzz");
+        assert_eq!(
+            style.into_owned().unwrap_err(),
+            IntoOwnedError::InvalidColor
+        );
+        let mut style = Style::classic();
+        style.text_shadow_opacity = Opacity::raw("x");
+        assert_eq!(
+            style.into_owned().unwrap_err(),
+            IntoOwnedError::InvalidOpacity
+        );
+    }
 
     #[test]
     fn test_opacity() {
         // Transparent
-        assert_eq!(Opacity::parse("0"), Some(Opacity("0")));
-        assert_eq!(Opacity::parse(".0"), Some(Opacity("0")));
-        assert_eq!(Opacity::parse(".00"), Some(Opacity("0")));
-        assert_eq!(Opacity::parse("0.00"), Some(Opacity("0")));
+        assert_eq!(Opacity::parse("0"), Some(Opacity::raw("0")));
+        assert_eq!(Opacity::parse(".0"), Some(Opacity::raw("0")));
+        assert_eq!(Opacity::parse(".00"), Some(Opacity::raw("0")));
+        assert_eq!(Opacity::parse("0.00"), Some(Opacity::raw("0")));
         // Opaque
-        assert_eq!(Opacity::parse("1"), Some(Opacity("1")));
-        assert_eq!(Opacity::parse("1.0"), Some(Opacity("1")));
-        assert_eq!(Opacity::parse("1.00"), Some(Opacity("1")));
+        assert_eq!(Opacity::parse("1"), Some(Opacity::raw("1")));
+        assert_eq!(Opacity::parse("1.0"), Some(Opacity::raw("1")));
+        assert_eq!(Opacity::parse("1.00"), Some(Opacity::raw("1")));
         // Fraction
-        assert_eq!(Opacity::parse(".1"), Some(Opacity(".1")));
-        assert_eq!(Opacity::parse("0.1"), Some(Opacity(".1")));
-        assert_eq!(Opacity::parse("0.11"), Some(Opacity(".11")));
+        assert_eq!(Opacity::parse(".1"), Some(Opacity::raw(".1")));
+        assert_eq!(Opacity::parse("0.1"), Some(Opacity::raw(".1")));
+        assert_eq!(Opacity::parse("0.11"), Some(Opacity::raw(".11")));
         // Invalid
         assert_eq!(Opacity::parse("2"), None);
         assert_eq!(Opacity::parse("0."), None);
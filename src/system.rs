@@ -0,0 +1,378 @@
+//! Discovery of installed system fonts, behind the `system-fonts` feature.
+//!
+//! Fonts are found by family name and weight with [`system_font`], or with
+//! [`system_font_in`] given the directories to search.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use ttf_parser::{name_id, FaceTables, Tag};
+
+use crate::{parse_font, CachedFont, FontWeight, TrueTypeFont, LINE_HEIGHT};
+
+/// The font directories searched for system fonts, relative to the home
+/// directory where they don't start with `/`.
+const SYSTEM_FONT_DIRS: &[&str] = &[
+    "/usr/share/fonts",
+    "/usr/local/share/fonts",
+    ".local/share/fonts",
+    ".fonts",
+];
+
+/// The tables read to match a face, being those required by `ttf-parser` and
+/// those with the family names, weight and style.
+const MATCH_TABLES: &[&[u8; 4]] = &[b"head", b"hhea", b"maxp", b"name", b"OS/2"];
+
+/// The match tables of a face, by tag.
+type MatchTables = Vec<([u8; 4], Vec<u8>)>;
+
+/// The most faces read from a font collection.
+const MAX_COLLECTION_FACES: u32 = 256;
+
+/// The longest table read to match a face.
+const MAX_MATCH_TABLE_LEN: u32 = 1 << 20;
+
+/// A font file found by [`system_font`].
+#[derive(Debug, Clone)]
+pub struct SystemFont {
+    path: PathBuf,
+    data: Vec<u8>,
+    index: u32,
+}
+
+impl SystemFont {
+    /// Returns the path of the font file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the index of the face within the font file.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Prepares the cached True Type Font for use in generating badges, as
+    /// with [`font`](crate::font).
+    pub fn font(&self) -> CachedFont<TrueTypeFont<'_>> {
+        let face = parse_font(&self.data, self.index).expect("system font was validated");
+        CachedFont::new(TrueTypeFont::new(&face, LINE_HEIGHT as f32, 0))
+    }
+}
+
+/// Finds a system font given a family name and weight.
+///
+/// Fonts are searched for in `$XDG_DATA_HOME/fonts`, `/usr/share/fonts`,
+/// `/usr/local/share/fonts`, `~/.local/share/fonts` and `~/.fonts`. See
+/// [`system_font_in`] for how fonts are matched.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// let system_font = badgen::system_font("Inter", badgen::FontWeight::Bold).unwrap();
+/// let mut font = system_font.font();
+/// ```
+pub fn system_font(family: &str, weight: FontWeight) -> Option<SystemFont> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let mut dirs = Vec::new();
+    if let Some(data_home) = env::var_os("XDG_DATA_HOME") {
+        dirs.push(PathBuf::from(data_home).join("fonts"));
+    }
+    for dir in SYSTEM_FONT_DIRS {
+        if dir.starts_with('/') {
+            dirs.push(PathBuf::from(dir));
+        } else if let Some(ref home) = home {
+            dirs.push(home.join(dir));
+        }
+    }
+    system_font_in(&dirs, family, weight)
+}
+
+/// Finds a font in the given directories, and their subdirectories, given a
+/// family name and weight.
+///
+/// Family names are matched ignoring case. Files named after the family are
+/// searched first, before every `.ttf`, `.otf` and `.ttc` file. Of the
+/// matching faces, upright faces with the closest weight are preferred.
+/// Unreadable and invalid files are skipped.
+///
+/// Only the tables needed to match faces are read, with just the best match
+/// read in full. Symbolic links to directories are followed, but each
+/// directory is only searched once.
+pub fn system_font_in<P>(dirs: &[P], family: &str, weight: FontWeight) -> Option<SystemFont>
+where
+    P: AsRef<Path>,
+{
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    for dir in dirs {
+        find_font_files(dir.as_ref(), &mut files, &mut visited);
+    }
+    files.sort();
+
+    let stem = normalize(family);
+    let (named, others): (Vec<_>, Vec<_>) = files.into_iter().partition(|path| {
+        path.file_stem()
+            .and_then(|s| s.to_str())
+            .is_some_and(|s| normalize(s).starts_with(&stem))
+    });
+    best_match(&named, family, weight).or_else(|| best_match(&others, family, weight))
+}
+
+fn find_font_files(dir: &Path, files: &mut Vec<PathBuf>, visited: &mut HashSet<PathBuf>) {
+    // Canonical paths avoid searching a directory twice through symbolic
+    // links, or forever through a loop of them.
+    let canonical = match fs::canonicalize(dir) {
+        Ok(canonical) => canonical,
+        Err(_) => return,
+    };
+    if !visited.insert(canonical) {
+        return;
+    }
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_dir = match entry.file_type() {
+            Ok(file_type) if file_type.is_symlink() => path.is_dir(),
+            Ok(file_type) => file_type.is_dir(),
+            Err(_) => continue,
+        };
+        if is_dir {
+            find_font_files(&path, files, visited);
+        } else if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            if ["ttf", "otf", "ttc"]
+                .iter()
+                .any(|e| ext.eq_ignore_ascii_case(e))
+            {
+                files.push(path);
+            }
+        }
+    }
+}
+
+/// Returns the face of the family in the files that best matches the weight.
+fn best_match(files: &[PathBuf], family: &str, weight: FontWeight) -> Option<SystemFont> {
+    let target = match weight {
+        FontWeight::Normal => 400,
+        FontWeight::Bold => 700,
+    };
+    let mut matches = Vec::new();
+    for path in files {
+        let faces = match read_match_tables(path) {
+            Ok(faces) => faces,
+            Err(_) => continue,
+        };
+        for (index, tables) in (0..).zip(&faces) {
+            let tables = tables
+                .iter()
+                .map(|(tag, data)| Ok((Tag::from_bytes(tag), Some(&data[..]))));
+            let face = match FaceTables::from_table_provider(tables) {
+                Ok(face) => face,
+                Err(_) => continue,
+            };
+            if !family_names(&face).any(|name| name.eq_ignore_ascii_case(family)) {
+                continue;
+            }
+            let score = (
+                face.is_italic() || face.is_oblique(),
+                (face.weight().to_number() as i32 - target).unsigned_abs() as u16,
+            );
+            matches.push((score, path, index));
+        }
+    }
+
+    // The sort is stable, so earlier files are preferred for equal scores.
+    matches.sort_by_key(|&(score, _, _)| score);
+    matches.into_iter().find_map(|(_, path, index)| {
+        let data = fs::read(path).ok()?;
+        parse_font(&data, index).ok()?;
+        Some(SystemFont {
+            path: path.clone(),
+            data,
+            index,
+        })
+    })
+}
+
+/// Reads the tables needed to match each face in a font file, without reading
+/// the whole file.
+fn read_match_tables(path: &Path) -> io::Result<Vec<MatchTables>> {
+    let mut file = File::open(path)?;
+    let mut header = [0; 12];
+    file.read_exact(&mut header)?;
+    let offsets = if &header[..4] == b"ttcf" {
+        let faces = read_u32(&header[8..]).min(MAX_COLLECTION_FACES);
+        let mut offsets = vec![0; faces as usize * 4];
+        file.read_exact(&mut offsets)?;
+        offsets.chunks(4).map(read_u32).collect()
+    } else {
+        vec![0]
+    };
+
+    let mut faces = Vec::with_capacity(offsets.len());
+    for offset in offsets {
+        file.seek(SeekFrom::Start(offset as u64))?;
+        file.read_exact(&mut header)?;
+        let num_tables = u16::from_be_bytes([header[4], header[5]]);
+        let mut records = vec![0; num_tables as usize * 16];
+        file.read_exact(&mut records)?;
+
+        let mut tables = Vec::new();
+        for record in records.chunks(16) {
+            let tag = [record[0], record[1], record[2], record[3]];
+            let length = read_u32(&record[12..]);
+            if !MATCH_TABLES.contains(&&tag) || length > MAX_MATCH_TABLE_LEN {
+                continue;
+            }
+            let mut data = vec![0; length as usize];
+            file.seek(SeekFrom::Start(read_u32(&record[8..]) as u64))?;
+            file.read_exact(&mut data)?;
+            tables.push((tag, data));
+        }
+        faces.push(tables);
+    }
+    Ok(faces)
+}
+
+fn read_u32(data: &[u8]) -> u32 {
+    u32::from_be_bytes([data[0], data[1], data[2], data[3]])
+}
+
+fn family_names<'a>(face: &'a FaceTables<'_>) -> impl Iterator<Item = String> + 'a {
+    face.names()
+        .filter(|name| {
+            name.name_id() == name_id::FAMILY || name.name_id() == name_id::TYPOGRAPHIC_FAMILY
+        })
+        .filter_map(|name| name.to_string())
+}
+
+/// Returns a name in lowercase without spaces, dashes or underscores.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(all(test, feature = "font-noto-sans"))]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    const NOTOSANS_DATA: &[u8] = include_bytes!("../data/fonts/notosans/NotoSans-Regular.ttf");
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("badgen-fonts-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("noto")).unwrap();
+        dir
+    }
+
+    /// Returns the font data with the weight class in the `OS/2` table set.
+    fn with_weight(weight: u16) -> Vec<u8> {
+        let mut data = NOTOSANS_DATA.to_vec();
+        let record = data.windows(4).position(|w| w == b"OS/2").unwrap();
+        let offset = &data[record + 8..record + 12];
+        let offset = u32::from_be_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize;
+        data[offset + 4..offset + 6].copy_from_slice(&weight.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn test_system_font_in() {
+        let dir = temp_dir("weight");
+        fs::write(dir.join("noto/NotoSans-Regular.ttf"), NOTOSANS_DATA).unwrap();
+        fs::write(dir.join("noto/NotoSans-Bold.ttf"), with_weight(700)).unwrap();
+        fs::write(dir.join("noto/NotoSans-Broken.ttf"), b"not a font").unwrap();
+        fs::write(dir.join("Renamed.otf"), with_weight(600)).unwrap();
+
+        let find = |family, weight| {
+            system_font_in(&[&dir], family, weight)
+                .map(|font| font.path().strip_prefix(&dir).unwrap().to_path_buf())
+        };
+        assert_eq!(
+            find("Noto Sans", FontWeight::Normal),
+            Some(PathBuf::from("noto/NotoSans-Regular.ttf"))
+        );
+        assert_eq!(
+            find("noto sans", FontWeight::Bold),
+            Some(PathBuf::from("noto/NotoSans-Bold.ttf"))
+        );
+        assert_eq!(find("Inter", FontWeight::Normal), None);
+
+        // Files not named after the family are searched if none are found.
+        fs::remove_file(dir.join("noto/NotoSans-Bold.ttf")).unwrap();
+        fs::remove_file(dir.join("noto/NotoSans-Regular.ttf")).unwrap();
+        assert_eq!(
+            find("Noto Sans", FontWeight::Bold),
+            Some(PathBuf::from("Renamed.otf"))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_font_files_symlinks() {
+        let dir = temp_dir("symlinks");
+        fs::write(dir.join("noto/NotoSans-Regular.ttf"), NOTOSANS_DATA).unwrap();
+        std::os::unix::fs::symlink(&dir, dir.join("noto/loop")).unwrap();
+        std::os::unix::fs::symlink(dir.join("noto"), dir.join("alias")).unwrap();
+
+        let mut files = Vec::new();
+        find_font_files(&dir, &mut files, &mut HashSet::new());
+        assert_eq!(files.len(), 1);
+        assert!(system_font_in(&[&dir], "Noto Sans", FontWeight::Normal).is_some());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_match_tables() {
+        let dir = temp_dir("tables");
+        let path = dir.join("noto/NotoSans-Regular.ttf");
+        fs::write(&path, NOTOSANS_DATA).unwrap();
+
+        let faces = read_match_tables(&path).unwrap();
+        assert_eq!(faces.len(), 1);
+        let tags: Vec<_> = faces[0].iter().map(|(tag, _)| tag).collect();
+        assert_eq!(tags, [b"OS/2", b"head", b"hhea", b"maxp", b"name"]);
+        let size: usize = faces[0].iter().map(|(_, data)| data.len()).sum();
+        assert!(size < NOTOSANS_DATA.len() / 10);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_system_font_render() {
+        let dir = temp_dir("render");
+        fs::write(dir.join("noto/NotoSans-Regular.ttf"), NOTOSANS_DATA).unwrap();
+
+        let system_font = system_font_in(&[&dir], "Noto Sans", FontWeight::Normal).unwrap();
+        assert_eq!(system_font.index(), 0);
+        let mut font = system_font.font();
+        let mut out = String::new();
+        crate::write_badge_with_font(
+            &mut out,
+            &crate::Style::classic(),
+            "passing",
+            Some("build"),
+            &mut font,
+            &mut String::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            crate::badge(&crate::Style::classic(), "passing", Some("build")).unwrap()
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,474 @@
+//! Parsing of badge request paths compatible with
+//! [badgen.net](https://badgen.net) and [shields.io](https://shields.io).
+//!
+//! Supported paths take the forms:
+//!
+//! ```text
+//! /badge/:label/:status/:color?query
+//! /badge/:label/:status?query
+//! /badge/:label-:status-:color?query
+//! /badge/:status-:color?query
+//! ```
+//!
+//! Path text uses shields escaping, where `--` is a dash, `__` is an
+//! underscore, `_` is a space and `%XX` is a percent encoded byte. Query
+//! values are form encoded, where `+` is a space and `%XX` is a percent
+//! encoded byte. Parsing borrows from the input, with escapes in text decoded
+//! as the text is rendered.
+//!
+//! ```rust
+//! let badge = badgen::url::parse("/badge/build/passing/green?style=flat").unwrap();
+//!
+//! println!("{}", badge.badge().unwrap());
+//! ```
+
+use alloc::string::String;
+use core::fmt::{self, Write};
+use core::str;
+
+use crate::{write_badge_with_text, Color, Font, Style};
+
+const BADGE_PATH_PREFIX: &str = "/badge/";
+
+/// A badge parsed from a request path.
+#[derive(Debug, Clone)]
+pub struct BadgeUrl<'a> {
+    /// The style of the badge.
+    pub style: Style<'a>,
+    /// The status of the badge.
+    pub status: UrlText<'a>,
+    /// The label of the badge, if any.
+    pub label: Option<UrlText<'a>>,
+    /// The `icon` query parameter, if any.
+    ///
+    /// Icons are not currently rendered.
+    pub icon: Option<UrlText<'a>>,
+}
+
+impl<'a> BadgeUrl<'a> {
+    /// Generate an SVG badge.
+    ///
+    /// Uses the default font provided by this library.
+    #[cfg(any(feature = "font-noto-sans", feature = "font-dejavu-sans"))]
+    pub fn badge(&self) -> Result<String, fmt::Error> {
+        let ttf_font = crate::style_font(&self.style);
+        let mut font = crate::font_for_style(&ttf_font, &self.style);
+        let mut scratch = String::with_capacity(4096);
+        let mut out = String::with_capacity(8192);
+        self.write_badge_with_font(&mut out, &mut font, &mut scratch)?;
+        Ok(out)
+    }
+
+    /// Writes an SVG badge to a [`fmt::Write`] given a font and scratch
+    /// space.
+    ///
+    /// See [`write_badge_with_font`](crate::write_badge_with_font).
+    pub fn write_badge_with_font<W, F>(
+        &self,
+        w: &mut W,
+        font: &mut F,
+        scratch: &mut String,
+    ) -> Result<(), fmt::Error>
+    where
+        W: fmt::Write,
+        F: Font,
+    {
+        write_badge_with_text(w, &self.style, self.status, self.label, font, scratch)
+    }
+}
+
+/// An error parsing a badge request path.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum UrlError {
+    /// The path does not start with `/badge/`.
+    NotBadgePath,
+    /// The path does not have a valid number of segments.
+    InvalidSegments,
+    /// The status color is invalid.
+    InvalidColor,
+    /// The label color is invalid.
+    InvalidLabelColor,
+}
+
+impl fmt::Display for UrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotBadgePath => "path is not a badge path",
+            Self::InvalidSegments => "path has an invalid number of segments",
+            Self::InvalidColor => "invalid color",
+            Self::InvalidLabelColor => "invalid label color",
+        })
+    }
+}
+
+/// Parses a badge request path, with an optional query string.
+///
+/// Supported query parameters are `style`, `color`, `labelColor`, `label` and
+/// `icon`. Styles are mapped as with [`style_for_name`], and unknown query
+/// parameters are ignored.
+pub fn parse(path_and_query: &str) -> Result<BadgeUrl<'_>, UrlError> {
+    let (path, query) = match path_and_query.split_once('?') {
+        Some((path, query)) => (path, query),
+        None => (path_and_query, ""),
+    };
+
+    let path = path
+        .strip_prefix(BADGE_PATH_PREFIX)
+        .ok_or(UrlError::NotBadgePath)?;
+    let path = path.strip_suffix(".svg").unwrap_or(path);
+
+    let slash_form = path.contains('/');
+    let mut segments = [""; 3];
+    let count = if slash_form {
+        split_into(path.split('/'), &mut segments)?
+    } else {
+        split_into(DashSplit(Some(path)), &mut segments)?
+    };
+
+    // The shields dash form always ends with a color.
+    let (label, status, color) = match (count, slash_form) {
+        (2, false) => (None, segments[0], Some(segments[1])),
+        (3, false) => (Some(segments[0]), segments[1], Some(segments[2])),
+        (2, true) => (Some(segments[0]), segments[1], None),
+        (3, true) => (Some(segments[0]), segments[1], Some(segments[2])),
+        _ => return Err(UrlError::InvalidSegments),
+    };
+
+    let mut badge = BadgeUrl {
+        style: Style::classic(),
+        status: UrlText::path(status),
+        label: label.filter(|l| !l.is_empty()).map(UrlText::path),
+        icon: None,
+    };
+
+    if let Some(color) = color {
+        badge.style.background = Color::parse_shields(color).ok_or(UrlError::InvalidColor)?;
+    }
+
+    let mut color = None;
+    let mut label_color = None;
+
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let mut buf = [0; QUERY_NAME_LEN];
+        match decode_query(key, &mut buf).unwrap_or_default() {
+            "style" => {
+                let mut buf = [0; QUERY_NAME_LEN];
+                let background = badge.style.background;
+                badge.style = style_for_name(decode_query(value, &mut buf).unwrap_or_default());
+                badge.style.background = background;
+            }
+            "color" => color = Some(value),
+            "labelColor" => label_color = Some(value),
+            "label" => badge.label = Some(UrlText::query(value)).filter(|l| !l.text.is_empty()),
+            "icon" => badge.icon = Some(UrlText::query(value)),
+            _ => {}
+        }
+    }
+
+    if let Some(color) = color {
+        badge.style.background = parse_query_color(color).ok_or(UrlError::InvalidColor)?;
+    }
+
+    if let Some(label_color) = label_color {
+        let label_color = parse_query_color(label_color).ok_or(UrlError::InvalidLabelColor)?;
+        badge.style.label_background = Some(label_color);
+    }
+
+    Ok(badge)
+}
+
+/// Returns the style for a [shields.io](https://shields.io) style name.
+///
+/// `flat` and `flat-square` map to [`Style::flat`], with everything else,
+/// including `plastic`, `for-the-badge`, `social` and unknown names, mapping
+/// to [`Style::classic`].
+pub fn style_for_name(name: &str) -> Style<'static> {
+    match name {
+        "flat" | "flat-square" => Style::flat(),
+        _ => Style::classic(),
+    }
+}
+
+/// The longest escaped query key or value decoded for comparison with known
+/// names, which is decoded on the stack rather than allocated.
+const QUERY_NAME_LEN: usize = 32;
+
+/// Decodes a form encoded query key or value for comparison with known names,
+/// borrowing if it has no escapes.
+///
+/// Returns `None` if escaped text decodes to more than [`QUERY_NAME_LEN`]
+/// bytes, as no key, style or color name is that long.
+fn decode_query<'a>(value: &'a str, buf: &'a mut [u8; QUERY_NAME_LEN]) -> Option<&'a str> {
+    if !value.contains(['%', '+']) {
+        return Some(value);
+    }
+    let mut writer = NameWriter { buf, len: 0 };
+    write!(writer, "{}", UrlText::query(value)).ok()?;
+    let len = writer.len;
+    str::from_utf8(&buf[..len]).ok()
+}
+
+/// Parses a color from a query value, converting decoded custom colors to
+/// [`Color::Rgb`] as they can't borrow from the query.
+fn parse_query_color(value: &str) -> Option<Color<'_>> {
+    if !value.contains(['%', '+']) {
+        return Color::parse_shields(value);
+    }
+    let mut buf = [0; QUERY_NAME_LEN];
+    Color::parse_shields(decode_query(value, &mut buf)?)?
+        .into_owned()
+        .ok()
+}
+
+/// Writes decoded text into a fixed buffer, failing once it is full.
+struct NameWriter<'a> {
+    buf: &'a mut [u8; QUERY_NAME_LEN],
+    len: usize,
+}
+
+impl<'a> fmt::Write for NameWriter<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.buf
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+fn split_into<'a, I>(iter: I, segments: &mut [&'a str; 3]) -> Result<usize, UrlError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut count = 0;
+    for segment in iter {
+        if count == segments.len() {
+            return Err(UrlError::InvalidSegments);
+        }
+        segments[count] = segment;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Splits on single dashes, leaving escaped `--` dashes intact.
+struct DashSplit<'a>(Option<&'a str>);
+
+impl<'a> Iterator for DashSplit<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let s = self.0?;
+        let bytes = s.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'-' {
+                if bytes.get(i + 1) == Some(&b'-') {
+                    i += 2;
+                    continue;
+                }
+                self.0 = Some(&s[i + 1..]);
+                return Some(&s[..i]);
+            }
+            i += 1;
+        }
+        self.0 = None;
+        Some(s)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Escaped text from a badge path or query string, decoded when displayed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UrlText<'a> {
+    text: &'a str,
+    query: bool,
+}
+
+impl<'a> UrlText<'a> {
+    /// Text from a badge path, with shields escaping.
+    pub fn path(text: &'a str) -> Self {
+        Self { text, query: false }
+    }
+
+    /// Text from a query value, with form encoding.
+    pub fn query(text: &'a str) -> Self {
+        Self { text, query: true }
+    }
+
+    /// Returns the raw escaped text.
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+}
+
+impl<'a> fmt::Display for UrlText<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = self.text;
+        let bytes = s.as_bytes();
+        let mut utf8 = [0u8; 4];
+        let mut utf8_len = 0;
+        let mut last = 0;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let (decoded, len) = match (bytes[i], bytes.get(i + 1).copied()) {
+                (b'%', _) => match percent_decode(&bytes[i..]) {
+                    Some(byte) => {
+                        f.write_str(&s[last..i])?;
+                        i += 3;
+                        last = i;
+                        utf8[utf8_len] = byte;
+                        utf8_len += 1;
+                        while utf8_len > 0 {
+                            match str::from_utf8(&utf8[..utf8_len]) {
+                                Ok(c) => {
+                                    f.write_str(c)?;
+                                    utf8_len = 0;
+                                }
+                                Err(err) => match err.error_len() {
+                                    // An incomplete character, waiting for more bytes.
+                                    None => break,
+                                    // Replaces only the invalid bytes, decoding the rest again.
+                                    Some(invalid) => {
+                                        f.write_char(char::REPLACEMENT_CHARACTER)?;
+                                        utf8.copy_within(invalid..utf8_len, 0);
+                                        utf8_len -= invalid;
+                                    }
+                                },
+                            }
+                        }
+                        continue;
+                    }
+                    None => (None, 1),
+                },
+                (b'+', _) if self.query => (Some(' '), 1),
+                (b'-', Some(b'-')) if !self.query => (Some('-'), 2),
+                (b'_', Some(b'_')) if !self.query => (Some('_'), 2),
+                (b'_', _) if !self.query => (Some(' '), 1),
+                _ => (None, 1),
+            };
+            if utf8_len > 0 {
+                // An incomplete percent encoded character.
+                f.write_char(char::REPLACEMENT_CHARACTER)?;
+                utf8_len = 0;
+            }
+            if let Some(c) = decoded {
+                f.write_str(&s[last..i])?;
+                f.write_char(c)?;
+                last = i + len;
+            }
+            i += len;
+        }
+
+        if utf8_len > 0 {
+            f.write_char(char::REPLACEMENT_CHARACTER)?;
+        }
+        f.write_str(&s[last..])
+    }
+}
+
+fn percent_decode(bytes: &[u8]) -> Option<u8> {
+    let hex = |b: u8| (b as char).to_digit(16);
+    match bytes {
+        [b'%', h, l, ..] => Some((hex(*h)? << 4 | hex(*l)?) as u8),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_parse_slash_form() {
+        let badge = parse("/badge/build/passing/green?labelColor=333&icon=rust").unwrap();
+        assert_eq!(badge.label, Some(UrlText::path("build")));
+        assert_eq!(badge.status, UrlText::path("passing"));
+        assert_eq!(badge.style.background, Color::Green);
+        assert_eq!(badge.style.label_background, Some(Color::Custom("333")));
+        assert_eq!(badge.icon, Some(UrlText::query("rust")));
+
+        let badge = parse("/badge//passing?style=flat&color=red").unwrap();
+        assert_eq!(badge.label, None);
+        assert_eq!(badge.style.background, Color::Red);
+        assert!(badge.style.gradient.is_none());
+    }
+
+    #[test]
+    fn test_parse_dash_form() {
+        let badge = parse("/badge/just--the__message-8A2BE2.svg").unwrap();
+        assert_eq!(badge.label, None);
+        assert_eq!(badge.status.to_string(), "just-the_message");
+        assert_eq!(badge.style.background, Color::Custom("8A2BE2"));
+
+        let badge = parse("/badge/any_text-you%20like-blue").unwrap();
+        assert_eq!(badge.label.unwrap().to_string(), "any text");
+        assert_eq!(badge.status.to_string(), "you like");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse("/foo/a/b").unwrap_err(), UrlError::NotBadgePath);
+        assert_eq!(parse("/badge/a").unwrap_err(), UrlError::InvalidSegments);
+        assert_eq!(
+            parse("/badge/a/b/c/d").unwrap_err(),
+            UrlError::InvalidSegments
+        );
+        assert_eq!(
+            parse("/badge/a-b-nope").unwrap_err(),
+            UrlError::InvalidColor
+        );
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let badge = parse("/badge/a/b?color=%23fff&labelColor=%2300ff00&label=a+b_c%20d").unwrap();
+        assert_eq!(badge.style.background, Color::Rgb(255, 255, 255));
+        assert_eq!(badge.style.label_background, Some(Color::Rgb(0, 255, 0)));
+        assert_eq!(badge.label.unwrap().to_string(), "a b_c d");
+
+        // Styles map as for endpoints, rather than being rejected.
+        for (style, flat) in [
+            ("flat-square", true),
+            ("for-the-badge", false),
+            ("x", false),
+        ]
+        .iter()
+        {
+            let url = format!("/badge/a/b/red?style={}", style);
+            let badge = parse(&url).unwrap();
+            assert_eq!(badge.style.gradient.is_none(), *flat, "{}", style);
+            assert_eq!(badge.style.background, Color::Red);
+        }
+        assert_eq!(
+            parse("/badge/a/b?color=%zz").unwrap_err(),
+            UrlError::InvalidColor
+        );
+
+        // Escaped keys and names are decoded without allocating.
+        let badge = parse("/badge/a/b?%73tyle=fl%61t&color=light%67rey").unwrap();
+        assert!(badge.style.gradient.is_none());
+        assert_eq!(badge.style.background, Color::Grey);
+        let long = format!("/badge/a/b?color={}", "%61".repeat(QUERY_NAME_LEN + 1));
+        assert_eq!(parse(&long).unwrap_err(), UrlError::InvalidColor);
+    }
+
+    #[test]
+    fn test_url_text_decode() {
+        assert_eq!(UrlText::path("caf%C3%A9").to_string(), "café");
+        assert_eq!(UrlText::path("100%25").to_string(), "100%");
+        assert_eq!(UrlText::path("bad%C3").to_string(), "bad\u{FFFD}");
+        assert_eq!(UrlText::path("50%").to_string(), "50%");
+        assert_eq!(UrlText::path("%C3%41").to_string(), "\u{FFFD}A");
+        assert_eq!(UrlText::path("%E2%82%C3%A9").to_string(), "\u{FFFD}é");
+        assert_eq!(UrlText::path("%FF%41").to_string(), "\u{FFFD}A");
+    }
+}
@@ -36,3 +36,39 @@ impl<'a> Escape<'a> {
         Ok(())
     }
 }
+
+/// Writes a number in its shortest form, without a leading zero or a
+/// trailing `.0`.
+pub(crate) fn write_number<W>(mut w: W, v: f32) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    let mut buf = ryu::Buffer::new();
+    let s = buf.format(v);
+    let s = s.strip_suffix(".0").unwrap_or(s);
+    w.write_str(s.strip_prefix('0').filter(|s| !s.is_empty()).unwrap_or(s))
+}
+
+/// A 32-bit FNV-1a hasher over written text.
+#[derive(Debug)]
+pub(crate) struct Fnv1a(u32);
+
+impl Fnv1a {
+    pub(crate) fn new() -> Self {
+        Self(0x811c_9dc5)
+    }
+
+    pub(crate) fn finish(&self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Write for Fnv1a {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for b in s.bytes() {
+            self.0 ^= b as u32;
+            self.0 = self.0.wrapping_mul(0x0100_0193);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use alloc::vec::Vec;
+
+use crate::FontError;
+
+const WOFF_HEADER_LEN: usize = 44;
+const WOFF_TABLE_LEN: usize = 20;
+pub(crate) const SFNT_HEADER_LEN: usize = 12;
+pub(crate) const SFNT_TABLE_LEN: usize = 16;
+
+/// Decodes a WOFF or WOFF2 font into TrueType or OpenType font data.
+///
+/// The decoded data can then be parsed with [`parse_font`](crate::parse_font).
+/// WOFF2 fonts with collections and transformed tables are supported.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// let woff = std::fs::read("brand.woff").unwrap();
+/// let data = badgen::decode_woff(&woff).unwrap();
+/// let face = badgen::parse_font(&data, 0).unwrap();
+/// let mut font = badgen::font(&face);
+/// ```
+pub fn decode_woff(data: &[u8]) -> Result<Vec<u8>, FontError> {
+    match data.get(..4) {
+        Some(b"wOFF") => {}
+        Some(b"wOF2") => return crate::woff2::decode_woff2(data),
+        _ => return Err(FontError::MalformedWoff),
+    }
+    let header = data
+        .get(..WOFF_HEADER_LEN)
+        .ok_or(FontError::MalformedWoff)?;
+    let flavor = &header[4..8];
+    let num_tables = read_u16(header, 12)?;
+    // The sfnt header stores the size of the table records in 16 bits.
+    if num_tables as usize * SFNT_TABLE_LEN > u16::MAX as usize {
+        return Err(FontError::MalformedWoff);
+    }
+
+    let directory_len = num_tables as usize * WOFF_TABLE_LEN;
+    let directory = data
+        .get(WOFF_HEADER_LEN..WOFF_HEADER_LEN + directory_len)
+        .ok_or(FontError::MalformedWoff)?;
+
+    // Tables follow the sfnt table records, each padded to four bytes.
+    let mut table_offset = SFNT_HEADER_LEN + num_tables as usize * SFNT_TABLE_LEN;
+    // The total size in the header is not trusted, and tables are only
+    // allocated as they are decompressed.
+    let mut sfnt = Vec::with_capacity(table_offset);
+    write_sfnt_header(&mut sfnt, flavor, num_tables);
+
+    let mut tables = Vec::with_capacity(num_tables as usize);
+    for entry in directory.chunks(WOFF_TABLE_LEN) {
+        let offset = read_u32(entry, 4)? as usize;
+        let comp_length = read_u32(entry, 8)? as usize;
+        let orig_length = read_u32(entry, 12)? as usize;
+        let compressed = offset
+            .checked_add(comp_length)
+            .and_then(|end| data.get(offset..end))
+            .ok_or(FontError::MalformedWoff)?;
+
+        sfnt.extend_from_slice(&entry[..4]);
+        sfnt.extend_from_slice(&entry[16..20]);
+        sfnt.extend_from_slice(&(table_offset as u32).to_be_bytes());
+        sfnt.extend_from_slice(&(orig_length as u32).to_be_bytes());
+        tables.push((compressed, orig_length));
+        table_offset += (orig_length + 3) & !3;
+    }
+
+    for (compressed, orig_length) in tables {
+        if compressed.len() == orig_length {
+            sfnt.extend_from_slice(compressed);
+        } else if compressed.len() < orig_length {
+            let table =
+                miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(compressed, orig_length)
+                    .map_err(|_| FontError::MalformedWoff)?;
+            if table.len() != orig_length {
+                return Err(FontError::MalformedWoff);
+            }
+            sfnt.extend_from_slice(&table);
+        } else {
+            return Err(FontError::MalformedWoff);
+        }
+        sfnt.resize((sfnt.len() + 3) & !3, 0);
+    }
+
+    Ok(sfnt)
+}
+
+pub(crate) fn write_sfnt_header(sfnt: &mut Vec<u8>, flavor: &[u8], num_tables: u16) {
+    // The largest power of two no greater than the number of tables.
+    let entry_selector = 15u16.saturating_sub(num_tables.leading_zeros() as u16);
+    let search_range = (1u16 << entry_selector) * SFNT_TABLE_LEN as u16;
+    let range_shift = (num_tables * SFNT_TABLE_LEN as u16).saturating_sub(search_range);
+
+    sfnt.extend_from_slice(flavor);
+    for v in [num_tables, search_range, entry_selector, range_shift].iter() {
+        sfnt.extend_from_slice(&v.to_be_bytes());
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, FontError> {
+    match data.get(offset..offset + 2) {
+        Some(&[a, b]) => Ok(u16::from_be_bytes([a, b])),
+        _ => Err(FontError::MalformedWoff),
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, FontError> {
+    match data.get(offset..offset + 4) {
+        Some(&[a, b, c, d]) => Ok(u32::from_be_bytes([a, b, c, d])),
+        _ => Err(FontError::MalformedWoff),
+    }
+}
+
+#[cfg(all(test, feature = "font-noto-sans"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    use crate::Font;
+
+    const NOTOSANS_DATA: &[u8] = include_bytes!("../data/fonts/notosans/NotoSans-Regular.ttf");
+
+    /// Encodes font data as WOFF, compressing tables where it is smaller.
+    fn encode_woff(sfnt: &[u8]) -> Vec<u8> {
+        let num_tables = u16::from_be_bytes([sfnt[4], sfnt[5]]);
+        let mut directory = Vec::new();
+        let mut tables = Vec::new();
+        let tables_start = WOFF_HEADER_LEN + num_tables as usize * WOFF_TABLE_LEN;
+        for record in sfnt[SFNT_HEADER_LEN..]
+            .chunks(SFNT_TABLE_LEN)
+            .take(num_tables as usize)
+        {
+            let start = read_u32(record, 8).unwrap() as usize;
+            let length = read_u32(record, 12).unwrap() as usize;
+            let table = &sfnt[start..start + length];
+            let offset = tables_start + tables.len();
+            let compressed = miniz_oxide::deflate::compress_to_vec_zlib(table, 6);
+            let stored = if compressed.len() < length {
+                &compressed[..]
+            } else {
+                table
+            };
+            directory.extend_from_slice(&record[..4]);
+            directory.extend_from_slice(&(offset as u32).to_be_bytes());
+            directory.extend_from_slice(&(stored.len() as u32).to_be_bytes());
+            directory.extend_from_slice(&(length as u32).to_be_bytes());
+            directory.extend_from_slice(&record[4..8]);
+            tables.extend_from_slice(stored);
+            tables.resize((tables.len() + 3) & !3, 0);
+        }
+
+        let mut woff = Vec::new();
+        woff.extend_from_slice(b"wOFF");
+        woff.extend_from_slice(&sfnt[..4]);
+        woff.extend_from_slice(&((tables_start + tables.len()) as u32).to_be_bytes());
+        woff.extend_from_slice(&num_tables.to_be_bytes());
+        woff.extend_from_slice(&[0; 2]);
+        woff.extend_from_slice(&(sfnt.len() as u32).to_be_bytes());
+        woff.extend_from_slice(&[0; 24]);
+        woff.extend_from_slice(&directory);
+        woff.extend_from_slice(&tables);
+        woff
+    }
+
+    #[test]
+    fn test_decode_woff() {
+        let woff = encode_woff(NOTOSANS_DATA);
+        assert!(woff.len() < NOTOSANS_DATA.len());
+        assert_eq!(
+            crate::parse_font(&woff, 0).unwrap_err(),
+            FontError::UndecodedWoff
+        );
+
+        let data = decode_woff(&woff).unwrap();
+        let mut decoded = crate::font_from_slice(&data, 0).unwrap();
+        let mut font = crate::font_from_slice(NOTOSANS_DATA, 0).unwrap();
+        for c in "badgen 0.1".chars() {
+            let path = font.render_glyph(c).unwrap().path.map(String::from);
+            assert_eq!(
+                decoded.render_glyph(c).unwrap().path.map(String::from),
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_woff_errors() {
+        let woff = encode_woff(NOTOSANS_DATA);
+        assert_eq!(
+            decode_woff(&woff[..woff.len() / 2]).unwrap_err(),
+            FontError::MalformedWoff
+        );
+        assert_eq!(
+            decode_woff(NOTOSANS_DATA).unwrap_err(),
+            FontError::MalformedWoff
+        );
+        assert_eq!(decode_woff(b"wOF2").unwrap_err(), FontError::MalformedWoff);
+    }
+}
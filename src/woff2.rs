@@ -0,0 +1,1130 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use brotli_decompressor::{
+    Allocator, BrotliDecompressStream, BrotliResult, BrotliState, SliceWrapper, SliceWrapperMut,
+};
+
+use crate::woff::{write_sfnt_header, SFNT_HEADER_LEN, SFNT_TABLE_LEN};
+use crate::FontError;
+
+const WOFF2_HEADER_LEN: usize = 48;
+
+/// The tags of known tables, by their index in the flags of a table
+/// directory entry.
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+    b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+    b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+    b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+    b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+/// The index in the flags of a table directory entry followed by a tag.
+const ARBITRARY_TAG: u8 = 63;
+
+/// The length of the header of a transformed `glyf` table.
+const GLYF_HEADER_LEN: usize = 36;
+
+/// The most bytes decompressed at a time, so the output grows with the data
+/// rather than being allocated up front from an untrusted length.
+const DECOMPRESS_CHUNK_LEN: usize = 1 << 16;
+
+// Flags of simple glyph points in the `glyf` table.
+const ON_CURVE_POINT: u8 = 0x01;
+const X_SHORT_VECTOR: u8 = 0x02;
+const Y_SHORT_VECTOR: u8 = 0x04;
+const X_IS_SAME_OR_POSITIVE: u8 = 0x10;
+const Y_IS_SAME_OR_POSITIVE: u8 = 0x20;
+const OVERLAP_SIMPLE: u8 = 0x40;
+
+// Flags of composite glyph components in the `glyf` table.
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+/// Decodes a WOFF2 font, reversing the `glyf`, `loca` and `hmtx` table
+/// transforms.
+pub(crate) fn decode_woff2(data: &[u8]) -> Result<Vec<u8>, FontError> {
+    let mut header = Reader::new(
+        data.get(..WOFF2_HEADER_LEN)
+            .ok_or(FontError::MalformedWoff)?,
+    );
+    header.skip(4)?;
+    let flavor = header.tag()?;
+    header.skip(4)?;
+    let num_tables = header.u16()?;
+    // The sfnt header stores the size of the table records in 16 bits.
+    if num_tables == 0 || num_tables as usize * SFNT_TABLE_LEN > u16::MAX as usize {
+        return Err(FontError::MalformedWoff);
+    }
+    header.skip(6)?;
+    let compressed_len = header.u32()? as usize;
+
+    let mut directory = Reader::new(&data[WOFF2_HEADER_LEN..]);
+    let mut entries = Vec::with_capacity(num_tables as usize);
+    for _ in 0..num_tables {
+        entries.push(TableEntry::read(&mut directory)?);
+    }
+    let fonts = if &flavor == b"ttcf" {
+        read_collection_directory(&mut directory, num_tables)?
+    } else {
+        vec![CollectionFont {
+            flavor,
+            tables: (0..num_tables).collect(),
+        }]
+    };
+
+    let mut stream_len = 0usize;
+    for entry in &entries {
+        stream_len = stream_len
+            .checked_add(entry.stream_len() as usize)
+            .ok_or(FontError::MalformedWoff)?;
+    }
+    let compressed = directory.bytes(compressed_len)?;
+    let stream = decompress(compressed, stream_len)?;
+
+    let tables = decode_tables(&entries, &fonts, &stream)?;
+    Ok(write_sfnt(&entries, &fonts, &tables))
+}
+
+/// A table in the table directory of a WOFF2 font.
+struct TableEntry {
+    tag: [u8; 4],
+    orig_length: u32,
+    /// The length of the transformed table, if it is transformed.
+    transform_length: Option<u32>,
+}
+
+impl TableEntry {
+    fn read(directory: &mut Reader<'_>) -> Result<Self, FontError> {
+        let flags = directory.u8()?;
+        let tag = match flags & 0x3f {
+            ARBITRARY_TAG => directory.tag()?,
+            index => *KNOWN_TAGS[index as usize],
+        };
+        let version = flags >> 6;
+        let orig_length = directory.base128()?;
+        // The null transform of `glyf` and `loca` is version 3, and of other
+        // tables version 0.
+        let transformed = match (&tag, version) {
+            (b"glyf", 0) | (b"loca", 0) | (b"hmtx", 1) => true,
+            (b"glyf", 3) | (b"loca", 3) => false,
+            (b"glyf", _) | (b"loca", _) => return Err(FontError::MalformedWoff),
+            (_, 0) => false,
+            _ => return Err(FontError::MalformedWoff),
+        };
+        let transform_length = if transformed {
+            Some(directory.base128()?)
+        } else {
+            None
+        };
+        Ok(Self {
+            tag,
+            orig_length,
+            transform_length,
+        })
+    }
+
+    /// The length of the table in the decompressed stream.
+    fn stream_len(&self) -> u32 {
+        self.transform_length.unwrap_or(self.orig_length)
+    }
+}
+
+/// A font of a collection, or the only font of a WOFF2 font.
+struct CollectionFont {
+    flavor: [u8; 4],
+    /// The indices of the tables of the font in the table directory.
+    tables: Vec<u16>,
+}
+
+fn read_collection_directory(
+    directory: &mut Reader<'_>,
+    num_tables: u16,
+) -> Result<Vec<CollectionFont>, FontError> {
+    directory.skip(4)?;
+    let num_fonts = directory.u255_16()?;
+    let mut fonts = Vec::with_capacity(num_fonts as usize);
+    for _ in 0..num_fonts {
+        let font_num_tables = directory.u255_16()?;
+        if font_num_tables == 0 || font_num_tables as usize * SFNT_TABLE_LEN > u16::MAX as usize {
+            return Err(FontError::MalformedWoff);
+        }
+        let flavor = directory.tag()?;
+        let mut tables = Vec::with_capacity(font_num_tables as usize);
+        for _ in 0..font_num_tables {
+            let index = directory.u255_16()?;
+            if index >= num_tables {
+                return Err(FontError::MalformedWoff);
+            }
+            tables.push(index);
+        }
+        fonts.push(CollectionFont { flavor, tables });
+    }
+    if fonts.is_empty() {
+        return Err(FontError::MalformedWoff);
+    }
+    Ok(fonts)
+}
+
+/// Decompresses the Brotli compressed tables, which must be exactly `len`
+/// bytes.
+fn decompress(input: &[u8], len: usize) -> Result<Vec<u8>, FontError> {
+    let mut state = BrotliState::new(HeapAlloc, HeapAlloc, HeapAlloc);
+    let mut output = Vec::new();
+    let mut available_in = input.len();
+    let mut input_offset = 0;
+    let mut total_out = 0;
+    loop {
+        // One byte more than expected is made available, so data past the
+        // expected length is an error rather than more output.
+        let start = output.len();
+        let end = start + (len.saturating_add(1) - start).min(DECOMPRESS_CHUNK_LEN);
+        output.resize(end, 0);
+        let mut available_out = end - start;
+        let mut output_offset = start;
+        let result = BrotliDecompressStream(
+            &mut available_in,
+            &mut input_offset,
+            input,
+            &mut available_out,
+            &mut output_offset,
+            &mut output,
+            &mut total_out,
+            &mut state,
+        );
+        output.truncate(output_offset);
+        match result {
+            BrotliResult::ResultSuccess if output.len() == len => return Ok(output),
+            BrotliResult::NeedsMoreOutput if output.len() <= len => {}
+            _ => return Err(FontError::MalformedWoff),
+        }
+    }
+}
+
+/// Allocates the state of the Brotli decoder on the heap.
+struct HeapAlloc;
+
+#[derive(Default)]
+struct HeapSlice<T>(Vec<T>);
+
+impl<T> SliceWrapper<T> for HeapSlice<T> {
+    fn slice(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T> SliceWrapperMut<T> for HeapSlice<T> {
+    fn slice_mut(&mut self) -> &mut [T] {
+        &mut self.0
+    }
+}
+
+impl<T: Clone + Default> Allocator<T> for HeapAlloc {
+    type AllocatedMemory = HeapSlice<T>;
+
+    fn alloc_cell(&mut self, len: usize) -> HeapSlice<T> {
+        HeapSlice(vec![T::default(); len])
+    }
+
+    fn free_cell(&mut self, _data: HeapSlice<T>) {}
+}
+
+/// Splits the decompressed stream into tables, reversing transforms.
+fn decode_tables(
+    entries: &[TableEntry],
+    fonts: &[CollectionFont],
+    stream: &[u8],
+) -> Result<Vec<Vec<u8>>, FontError> {
+    let mut stream = Reader::new(stream);
+    let mut data = Vec::with_capacity(entries.len());
+    for entry in entries {
+        data.push(stream.bytes(entry.stream_len() as usize)?);
+    }
+
+    let mut tables: Vec<Option<Vec<u8>>> = entries
+        .iter()
+        .zip(&data)
+        .map(|(entry, data)| match entry.transform_length {
+            Some(_) => None,
+            None => Some(data.to_vec()),
+        })
+        .collect();
+
+    // Transformed tables are reconstructed from other tables of their font.
+    for font in fonts {
+        let find = |tag: &[u8; 4]| {
+            font.tables
+                .iter()
+                .map(|&index| index as usize)
+                .find(|&index| &entries[index].tag == tag)
+        };
+        let glyf = find(b"glyf");
+        let mut x_mins = None;
+        if let Some(glyf) = glyf.filter(|&glyf| tables[glyf].is_none()) {
+            // The `loca` table is reconstructed with the `glyf` table.
+            let loca = find(b"loca")
+                .filter(|&loca| entries[loca].transform_length == Some(0))
+                .ok_or(FontError::MalformedWoff)?;
+            let reconstructed = reconstruct_glyf(data[glyf])?;
+            if reconstructed.loca.len() != entries[loca].orig_length as usize {
+                return Err(FontError::MalformedWoff);
+            }
+            tables[glyf] = Some(reconstructed.glyf);
+            tables[loca] = Some(reconstructed.loca);
+            x_mins = Some(reconstructed.x_mins);
+        }
+        if let Some(hmtx) = find(b"hmtx").filter(|&hmtx| tables[hmtx].is_none()) {
+            // Left side bearings are taken from the glyphs, so the `glyf`
+            // table must be transformed too.
+            let x_mins = x_mins.as_deref().ok_or(FontError::MalformedWoff)?;
+            let hhea = find(b"hhea")
+                .and_then(|hhea| tables[hhea].as_deref())
+                .ok_or(FontError::MalformedWoff)?;
+            let mut hhea = Reader::new(hhea);
+            hhea.skip(34)?;
+            let num_h_metrics = hhea.u16()?;
+            tables[hmtx] = Some(reconstruct_hmtx(data[hmtx], num_h_metrics, x_mins)?);
+        }
+    }
+
+    tables
+        .into_iter()
+        .map(|table| table.ok_or(FontError::MalformedWoff))
+        .collect()
+}
+
+/// A `glyf` table reconstructed from its transform.
+struct Glyf {
+    glyf: Vec<u8>,
+    loca: Vec<u8>,
+    /// The minimum x of each glyph.
+    x_mins: Vec<i16>,
+}
+
+fn reconstruct_glyf(data: &[u8]) -> Result<Glyf, FontError> {
+    let mut header = Reader::new(data);
+    header.skip(2)?;
+    let option_flags = header.u16()?;
+    let num_glyphs = header.u16()? as usize;
+    let index_format = header.u16()?;
+    let mut streams = Reader::new(
+        data.get(GLYF_HEADER_LEN..)
+            .ok_or(FontError::MalformedWoff)?,
+    );
+    let mut stream = || -> Result<Reader<'_>, FontError> {
+        let len = header.u32()? as usize;
+        Ok(Reader::new(streams.bytes(len)?))
+    };
+    let mut n_contours = stream()?;
+    let mut n_points = stream()?;
+    let mut flags = stream()?;
+    let mut glyphs = stream()?;
+    let mut composites = stream()?;
+    let mut bboxes = stream()?;
+    let mut instructions = stream()?;
+    let bbox_bitmap = bboxes.bytes(num_glyphs.div_ceil(32) * 4)?;
+    let overlap_bitmap = if option_flags & 1 != 0 {
+        Some(streams.bytes(num_glyphs.div_ceil(8))?)
+    } else {
+        None
+    };
+    let bit = |bitmap: &[u8], index: usize| bitmap[index / 8] & (0x80 >> (index % 8)) != 0;
+
+    let mut glyf = Vec::new();
+    let mut offsets = Vec::with_capacity(num_glyphs + 1);
+    let mut x_mins = Vec::with_capacity(num_glyphs);
+    for index in 0..num_glyphs {
+        offsets.push(glyf.len());
+        let contours = n_contours.i16()?;
+        let explicit_bbox = bit(bbox_bitmap, index);
+        let x_min = match contours {
+            0 if !explicit_bbox => 0,
+            -1 if explicit_bbox => {
+                let bbox = bboxes.bytes(8)?;
+                let (components, has_instructions) = composite_glyph(&mut composites)?;
+                glyf.extend_from_slice(&contours.to_be_bytes());
+                glyf.extend_from_slice(bbox);
+                glyf.extend_from_slice(components);
+                if has_instructions {
+                    let len = glyphs.u255_16()?;
+                    glyf.extend_from_slice(&len.to_be_bytes());
+                    glyf.extend_from_slice(instructions.bytes(len as usize)?);
+                }
+                i16::from_be_bytes([bbox[0], bbox[1]])
+            }
+            contours if contours > 0 => {
+                let overlap = overlap_bitmap.is_some_and(|bitmap| bit(bitmap, index));
+                let mut glyph = SimpleGlyph {
+                    contours: contours as u16,
+                    n_points: &mut n_points,
+                    flags: &mut flags,
+                    glyphs: &mut glyphs,
+                    bboxes: &mut bboxes,
+                    instructions: &mut instructions,
+                };
+                glyph.write(&mut glyf, explicit_bbox, overlap)?
+            }
+            _ => return Err(FontError::MalformedWoff),
+        };
+        x_mins.push(x_min);
+        glyf.resize((glyf.len() + 3) & !3, 0);
+    }
+    offsets.push(glyf.len());
+
+    let mut loca = Vec::new();
+    for offset in offsets {
+        match index_format {
+            0 if offset / 2 <= u16::MAX as usize => {
+                loca.extend_from_slice(&((offset / 2) as u16).to_be_bytes())
+            }
+            1 if offset <= u32::MAX as usize => {
+                loca.extend_from_slice(&(offset as u32).to_be_bytes())
+            }
+            _ => return Err(FontError::MalformedWoff),
+        }
+    }
+    Ok(Glyf { glyf, loca, x_mins })
+}
+
+/// Reads the components of a composite glyph, returning them and whether the
+/// glyph has instructions.
+fn composite_glyph<'a>(composites: &mut Reader<'a>) -> Result<(&'a [u8], bool), FontError> {
+    let start = composites.data;
+    let mut has_instructions = false;
+    loop {
+        let flags = composites.u16()?;
+        has_instructions |= flags & WE_HAVE_INSTRUCTIONS != 0;
+        // The glyph index and arguments.
+        let mut len = if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            6
+        } else {
+            4
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            len += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            len += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            len += 8;
+        }
+        composites.skip(len)?;
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    let len = start.len() - composites.data.len();
+    Ok((&start[..len], has_instructions))
+}
+
+/// The streams a simple glyph is read from.
+struct SimpleGlyph<'r, 'a> {
+    contours: u16,
+    n_points: &'r mut Reader<'a>,
+    flags: &'r mut Reader<'a>,
+    glyphs: &'r mut Reader<'a>,
+    bboxes: &'r mut Reader<'a>,
+    instructions: &'r mut Reader<'a>,
+}
+
+impl<'r, 'a> SimpleGlyph<'r, 'a> {
+    /// Writes the glyph to a `glyf` table, returning its minimum x.
+    fn write(
+        &mut self,
+        glyf: &mut Vec<u8>,
+        explicit_bbox: bool,
+        overlap: bool,
+    ) -> Result<i16, FontError> {
+        let mut end_points = Vec::with_capacity(self.contours as usize);
+        let mut num_points = 0u32;
+        for _ in 0..self.contours {
+            let points = self.n_points.u255_16()?;
+            if points == 0 {
+                return Err(FontError::MalformedWoff);
+            }
+            num_points += points as u32;
+            if num_points > u16::MAX as u32 + 1 {
+                return Err(FontError::MalformedWoff);
+            }
+            end_points.push((num_points - 1) as u16);
+        }
+
+        let mut point_flags = Vec::with_capacity(num_points as usize);
+        let mut xs = Vec::new();
+        let mut ys = Vec::new();
+        let (mut x, mut y) = (0i32, 0i32);
+        let mut bbox = [i32::MAX, i32::MAX, i32::MIN, i32::MIN];
+        for &flag in self.flags.bytes(num_points as usize)? {
+            let (dx, dy) = triplet(flag & 0x7f, self.glyphs)?;
+            let mut point_flag = if flag & 0x80 == 0 { ON_CURVE_POINT } else { 0 };
+            point_flag |= write_coordinate(&mut xs, dx, X_SHORT_VECTOR, X_IS_SAME_OR_POSITIVE)?;
+            point_flag |= write_coordinate(&mut ys, dy, Y_SHORT_VECTOR, Y_IS_SAME_OR_POSITIVE)?;
+            point_flags.push(point_flag);
+            x += dx;
+            y += dy;
+            bbox = [
+                bbox[0].min(x),
+                bbox[1].min(y),
+                bbox[2].max(x),
+                bbox[3].max(y),
+            ];
+        }
+        if overlap {
+            point_flags[0] |= OVERLAP_SIMPLE;
+        }
+        let instruction_len = self.glyphs.u255_16()?;
+        let instructions = self.instructions.bytes(instruction_len as usize)?;
+
+        glyf.extend_from_slice(&self.contours.to_be_bytes());
+        let x_min = if explicit_bbox {
+            let bbox = self.bboxes.bytes(8)?;
+            glyf.extend_from_slice(bbox);
+            i16::from_be_bytes([bbox[0], bbox[1]])
+        } else {
+            for &v in bbox.iter() {
+                let v = i16::try_from(v).map_err(|_| FontError::MalformedWoff)?;
+                glyf.extend_from_slice(&v.to_be_bytes());
+            }
+            bbox[0] as i16
+        };
+        for end_point in end_points {
+            glyf.extend_from_slice(&end_point.to_be_bytes());
+        }
+        glyf.extend_from_slice(&instruction_len.to_be_bytes());
+        glyf.extend_from_slice(instructions);
+        glyf.extend_from_slice(&point_flags);
+        glyf.extend_from_slice(&xs);
+        glyf.extend_from_slice(&ys);
+        Ok(x_min)
+    }
+}
+
+/// Decodes the coordinate deltas of a point from its flag and the glyph
+/// stream.
+fn triplet(flag: u8, glyphs: &mut Reader<'_>) -> Result<(i32, i32), FontError> {
+    let with_sign = |flag: u8, value: i32| if flag & 1 != 0 { value } else { -value };
+    let flag32 = flag as i32;
+    Ok(match flag {
+        0..=9 => {
+            let b0 = glyphs.u8()? as i32;
+            (0, with_sign(flag, ((flag32 & 14) << 7) + b0))
+        }
+        10..=19 => {
+            let b0 = glyphs.u8()? as i32;
+            (with_sign(flag, (((flag32 - 10) & 14) << 7) + b0), 0)
+        }
+        20..=83 => {
+            let b = flag32 - 20;
+            let b0 = glyphs.u8()? as i32;
+            (
+                with_sign(flag, 1 + (b & 0x30) + (b0 >> 4)),
+                with_sign(flag >> 1, 1 + ((b & 0x0c) << 2) + (b0 & 0x0f)),
+            )
+        }
+        84..=119 => {
+            let b = flag32 - 84;
+            let (b0, b1) = (glyphs.u8()? as i32, glyphs.u8()? as i32);
+            (
+                with_sign(flag, 1 + ((b / 12) << 8) + b0),
+                with_sign(flag >> 1, 1 + (((b % 12) >> 2) << 8) + b1),
+            )
+        }
+        120..=123 => {
+            let (b0, b1, b2) = (
+                glyphs.u8()? as i32,
+                glyphs.u8()? as i32,
+                glyphs.u8()? as i32,
+            );
+            (
+                with_sign(flag, (b0 << 4) + (b1 >> 4)),
+                with_sign(flag >> 1, ((b1 & 0x0f) << 8) + b2),
+            )
+        }
+        _ => {
+            let (x, y) = (glyphs.u16()? as i32, glyphs.u16()? as i32);
+            (with_sign(flag, x), with_sign(flag >> 1, y))
+        }
+    })
+}
+
+/// Writes a coordinate delta of a simple glyph, returning its point flags.
+fn write_coordinate(
+    coordinates: &mut Vec<u8>,
+    delta: i32,
+    short: u8,
+    same_or_positive: u8,
+) -> Result<u8, FontError> {
+    Ok(match delta {
+        0 => same_or_positive,
+        -255..=255 => {
+            coordinates.push(delta.unsigned_abs() as u8);
+            if delta > 0 {
+                short | same_or_positive
+            } else {
+                short
+            }
+        }
+        _ => {
+            let delta = i16::try_from(delta).map_err(|_| FontError::MalformedWoff)?;
+            coordinates.extend_from_slice(&delta.to_be_bytes());
+            0
+        }
+    })
+}
+
+/// Reconstructs a transformed `hmtx` table, taking omitted left side bearings
+/// from the minimum x of glyphs.
+fn reconstruct_hmtx(data: &[u8], num_h_metrics: u16, x_mins: &[i16]) -> Result<Vec<u8>, FontError> {
+    let mut data = Reader::new(data);
+    let flags = data.u8()?;
+    let num_glyphs = x_mins.len();
+    let num_h_metrics = num_h_metrics as usize;
+    // Either kind of left side bearing is omitted, and no other flags are set.
+    if flags & 3 == 0 || flags & !3 != 0 || num_h_metrics == 0 || num_h_metrics > num_glyphs {
+        return Err(FontError::MalformedWoff);
+    }
+    let mut advances = Reader::new(data.bytes(num_h_metrics * 2)?);
+    let mut lsbs = |omitted: bool, len: usize| {
+        Ok(if omitted {
+            None
+        } else {
+            Some(Reader::new(data.bytes(len * 2)?))
+        })
+    };
+    let mut proportional = lsbs(flags & 1 != 0, num_h_metrics)?;
+    let mut monospaced = lsbs(flags & 2 != 0, num_glyphs - num_h_metrics)?;
+
+    let mut hmtx = Vec::with_capacity(num_h_metrics * 2 + num_glyphs * 2);
+    for (index, &x_min) in x_mins.iter().enumerate() {
+        if index < num_h_metrics {
+            hmtx.extend_from_slice(&advances.u16()?.to_be_bytes());
+        }
+        let lsbs = if index < num_h_metrics {
+            proportional.as_mut()
+        } else {
+            monospaced.as_mut()
+        };
+        let lsb = match lsbs {
+            Some(lsbs) => lsbs.i16()?,
+            None => x_min,
+        };
+        hmtx.extend_from_slice(&lsb.to_be_bytes());
+    }
+    Ok(hmtx)
+}
+
+/// Writes the decoded tables as a font, or a font collection.
+fn write_sfnt(entries: &[TableEntry], fonts: &[CollectionFont], tables: &[Vec<u8>]) -> Vec<u8> {
+    let collection = fonts.len() > 1 || fonts[0].tables.len() != entries.len();
+    let header_len = if collection { 12 + fonts.len() * 4 } else { 0 };
+    let directories_len: usize = fonts
+        .iter()
+        .map(|font| SFNT_HEADER_LEN + font.tables.len() * SFNT_TABLE_LEN)
+        .sum();
+
+    // Tables follow the table directories, each padded to four bytes.
+    let mut offset = header_len + directories_len;
+    let mut offsets = Vec::with_capacity(tables.len());
+    for table in tables {
+        offsets.push(offset as u32);
+        offset += (table.len() + 3) & !3;
+    }
+
+    let mut sfnt = Vec::with_capacity(header_len + directories_len);
+    if collection {
+        sfnt.extend_from_slice(b"ttcf");
+        sfnt.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+        sfnt.extend_from_slice(&(fonts.len() as u32).to_be_bytes());
+        let mut offset = header_len;
+        for font in fonts {
+            sfnt.extend_from_slice(&(offset as u32).to_be_bytes());
+            offset += SFNT_HEADER_LEN + font.tables.len() * SFNT_TABLE_LEN;
+        }
+    }
+    for font in fonts {
+        write_sfnt_header(&mut sfnt, &font.flavor, font.tables.len() as u16);
+        // Table records are sorted by tag.
+        let mut indices: Vec<usize> = font.tables.iter().map(|&index| index as usize).collect();
+        indices.sort_by_key(|&index| entries[index].tag);
+        for index in indices {
+            let table = &tables[index];
+            sfnt.extend_from_slice(&entries[index].tag);
+            sfnt.extend_from_slice(&checksum(table).to_be_bytes());
+            sfnt.extend_from_slice(&offsets[index].to_be_bytes());
+            sfnt.extend_from_slice(&(table.len() as u32).to_be_bytes());
+        }
+    }
+    for table in tables {
+        sfnt.extend_from_slice(table);
+        sfnt.resize((sfnt.len() + 3) & !3, 0);
+    }
+    sfnt
+}
+
+/// The checksum of a table, the sum of its big-endian 32-bit words.
+fn checksum(table: &[u8]) -> u32 {
+    table.chunks(4).fold(0u32, |sum, word| {
+        let mut bytes = [0; 4];
+        bytes[..word.len()].copy_from_slice(word);
+        sum.wrapping_add(u32::from_be_bytes(bytes))
+    })
+}
+
+/// Reads big-endian values, failing on truncated data.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], FontError> {
+        if len > self.data.len() {
+            return Err(FontError::MalformedWoff);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), FontError> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, FontError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, FontError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn i16(&mut self) -> Result<i16, FontError> {
+        self.u16().map(|v| v as i16)
+    }
+
+    fn u32(&mut self) -> Result<u32, FontError> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn tag(&mut self) -> Result<[u8; 4], FontError> {
+        self.u32().map(u32::to_be_bytes)
+    }
+
+    /// Reads a `UIntBase128`, a variable length integer of up to five bytes.
+    fn base128(&mut self) -> Result<u32, FontError> {
+        let mut value = 0u32;
+        for i in 0..5 {
+            let byte = self.u8()?;
+            // Leading zeros and values past 32 bits are invalid.
+            if (i == 0 && byte == 0x80) || value > u32::MAX >> 7 {
+                return Err(FontError::MalformedWoff);
+            }
+            value = (value << 7) | (byte & 0x7f) as u32;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(FontError::MalformedWoff)
+    }
+
+    /// Reads a `255UInt16`, a variable length integer of up to three bytes.
+    fn u255_16(&mut self) -> Result<u16, FontError> {
+        const ONE_MORE_BYTE_CODE2: u8 = 254;
+        const ONE_MORE_BYTE_CODE1: u8 = 255;
+        const WORD_CODE: u8 = 253;
+        const LOWEST_U_CODE: u16 = 253;
+        Ok(match self.u8()? {
+            WORD_CODE => self.u16()?,
+            ONE_MORE_BYTE_CODE1 => self.u8()? as u16 + LOWEST_U_CODE,
+            ONE_MORE_BYTE_CODE2 => self.u8()? as u16 + LOWEST_U_CODE * 2,
+            code => code as u16,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "font-noto-sans"))]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use alloc::string::String;
+    use std::io::Write;
+
+    use crate::{decode_woff, Font};
+
+    const NOTOSANS_DATA: &[u8] = include_bytes!("../data/fonts/notosans/NotoSans-Regular.ttf");
+
+    const REPEAT_FLAG: u8 = 0x08;
+
+    /// The tables of font data by tag.
+    fn tables(sfnt: &[u8]) -> Vec<([u8; 4], &[u8])> {
+        let num_tables = u16::from_be_bytes([sfnt[4], sfnt[5]]) as usize;
+        sfnt[SFNT_HEADER_LEN..]
+            .chunks(SFNT_TABLE_LEN)
+            .take(num_tables)
+            .map(|record| {
+                let mut record = Reader::new(record);
+                let tag = record.tag().unwrap();
+                record.skip(4).unwrap();
+                let offset = record.u32().unwrap() as usize;
+                let len = record.u32().unwrap() as usize;
+                (tag, &sfnt[offset..offset + len])
+            })
+            .collect()
+    }
+
+    fn table<'a>(tables: &[([u8; 4], &'a [u8])], tag: &[u8; 4]) -> &'a [u8] {
+        tables.iter().find(|(t, _)| t == tag).unwrap().1
+    }
+
+    fn write_base128(out: &mut Vec<u8>, value: u32) {
+        let len = (1..5).take_while(|i| value >> (7 * i) != 0).count() + 1;
+        for i in (0..len).rev() {
+            let more = if i > 0 { 0x80 } else { 0 };
+            out.push(((value >> (7 * i)) & 0x7f) as u8 | more);
+        }
+    }
+
+    fn write_u255_16(out: &mut Vec<u8>, value: u16) {
+        match value {
+            0..=252 => out.push(value as u8),
+            253..=505 => out.extend_from_slice(&[255, (value - 253) as u8]),
+            506..=761 => out.extend_from_slice(&[254, (value - 506) as u8]),
+            _ => {
+                out.push(253);
+                out.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+    }
+
+    /// Encodes the coordinate deltas of a point, like the reference encoder.
+    fn write_triplet(flags: &mut Vec<u8>, glyphs: &mut Vec<u8>, on_curve: bool, x: i32, y: i32) {
+        let (abs_x, abs_y) = (x.abs(), y.abs());
+        let on_curve_bit = if on_curve { 0 } else { 128 };
+        let x_sign_bit = (x >= 0) as i32;
+        let y_sign_bit = (y >= 0) as i32;
+        let xy_sign_bits = x_sign_bit + 2 * y_sign_bit;
+        let (flag, bytes) = if x == 0 && abs_y < 1280 {
+            let flag = ((abs_y & 0xf00) >> 7) + y_sign_bit;
+            (flag, vec![abs_y & 0xff])
+        } else if y == 0 && abs_x < 1280 {
+            let flag = 10 + ((abs_x & 0xf00) >> 7) + x_sign_bit;
+            (flag, vec![abs_x & 0xff])
+        } else if abs_x < 65 && abs_y < 65 {
+            let flag = 20 + ((abs_x - 1) & 0x30) + (((abs_y - 1) & 0x30) >> 2) + xy_sign_bits;
+            (flag, vec![(((abs_x - 1) & 0xf) << 4) | ((abs_y - 1) & 0xf)])
+        } else if abs_x < 769 && abs_y < 769 {
+            let flag = 84
+                + 12 * (((abs_x - 1) & 0x300) >> 8)
+                + (((abs_y - 1) & 0x300) >> 6)
+                + xy_sign_bits;
+            (flag, vec![(abs_x - 1) & 0xff, (abs_y - 1) & 0xff])
+        } else if abs_x < 4096 && abs_y < 4096 {
+            let bytes = vec![
+                abs_x >> 4,
+                ((abs_x & 0xf) << 4) | (abs_y >> 8),
+                abs_y & 0xff,
+            ];
+            (120 + xy_sign_bits, bytes)
+        } else {
+            let bytes = vec![abs_x >> 8, abs_x & 0xff, abs_y >> 8, abs_y & 0xff];
+            (124 + xy_sign_bits, bytes)
+        };
+        flags.push((flag + on_curve_bit) as u8);
+        glyphs.extend(bytes.into_iter().map(|b| b as u8));
+    }
+
+    /// Transforms the `glyf` and `loca` tables, returning the transformed
+    /// `glyf` table.
+    fn transform_glyf(glyf: &[u8], loca: &[u8], index_format: u16) -> Vec<u8> {
+        let offset = |index: usize| match index_format {
+            0 => u16::from_be_bytes([loca[index * 2], loca[index * 2 + 1]]) as usize * 2,
+            _ => {
+                let bytes = &loca[index * 4..index * 4 + 4];
+                u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize
+            }
+        };
+        let num_glyphs = loca.len() / (2 << index_format) - 1;
+        let mut n_contours = Vec::new();
+        let mut n_points = Vec::new();
+        let mut flags = Vec::new();
+        let mut glyphs = Vec::new();
+        let mut composites = Vec::new();
+        let mut bboxes = vec![0; num_glyphs.div_ceil(32) * 4];
+        let mut instructions = Vec::new();
+        for index in 0..num_glyphs {
+            let data = &glyf[offset(index)..offset(index + 1)];
+            if data.is_empty() {
+                n_contours.extend_from_slice(&0i16.to_be_bytes());
+                continue;
+            }
+            let mut glyph = Reader::new(data);
+            let contours = glyph.i16().unwrap();
+            let bbox = glyph.bytes(8).unwrap();
+            n_contours.extend_from_slice(&contours.to_be_bytes());
+            let mut explicit_bbox = || {
+                bboxes[index / 8] |= 0x80 >> (index % 8);
+                bboxes.extend_from_slice(bbox);
+            };
+            if contours < 0 {
+                explicit_bbox();
+                let (components, has_instructions) = composite_glyph(&mut glyph).unwrap();
+                composites.extend_from_slice(components);
+                if has_instructions {
+                    let len = glyph.u16().unwrap();
+                    write_u255_16(&mut glyphs, len);
+                    instructions.extend_from_slice(glyph.bytes(len as usize).unwrap());
+                }
+                continue;
+            }
+
+            let mut num_points = 0;
+            for _ in 0..contours {
+                let end_point = glyph.u16().unwrap();
+                write_u255_16(&mut n_points, end_point + 1 - num_points);
+                num_points = end_point + 1;
+            }
+            let instruction_len = glyph.u16().unwrap();
+            let glyph_instructions = glyph.bytes(instruction_len as usize).unwrap();
+            let mut point_flags = Vec::new();
+            while point_flags.len() < num_points as usize {
+                let flag = glyph.u8().unwrap();
+                point_flags.push(flag);
+                if flag & REPEAT_FLAG != 0 {
+                    for _ in 0..glyph.u8().unwrap() {
+                        point_flags.push(flag);
+                    }
+                }
+            }
+            let mut deltas = |short: u8, same_or_positive: u8| -> Vec<i32> {
+                let mut read = |flag: u8| match (flag & short != 0, flag & same_or_positive != 0) {
+                    (true, true) => glyph.u8().unwrap() as i32,
+                    (true, false) => -(glyph.u8().unwrap() as i32),
+                    (false, true) => 0,
+                    (false, false) => glyph.i16().unwrap() as i32,
+                };
+                point_flags.iter().map(|&flag| read(flag)).collect()
+            };
+            let dxs = deltas(X_SHORT_VECTOR, X_IS_SAME_OR_POSITIVE);
+            let dys = deltas(Y_SHORT_VECTOR, Y_IS_SAME_OR_POSITIVE);
+
+            let (mut x, mut y) = (0, 0);
+            let mut computed = [i32::MAX, i32::MAX, i32::MIN, i32::MIN];
+            for ((&flag, &dx), &dy) in point_flags.iter().zip(&dxs).zip(&dys) {
+                let on_curve = flag & ON_CURVE_POINT != 0;
+                write_triplet(&mut flags, &mut glyphs, on_curve, dx, dy);
+                x += dx;
+                y += dy;
+                computed = [
+                    computed[0].min(x),
+                    computed[1].min(y),
+                    computed[2].max(x),
+                    computed[3].max(y),
+                ];
+            }
+            write_u255_16(&mut glyphs, instruction_len);
+            instructions.extend_from_slice(glyph_instructions);
+            // The bounding box is only stored if it differs from the points.
+            let stored: Vec<i32> = bbox
+                .chunks(2)
+                .map(|v| i16::from_be_bytes([v[0], v[1]]) as i32)
+                .collect();
+            if stored != computed {
+                explicit_bbox();
+            }
+        }
+
+        let mut transformed = Vec::new();
+        for value in [0, 0, num_glyphs as u16, index_format].iter() {
+            transformed.extend_from_slice(&value.to_be_bytes());
+        }
+        let streams = [
+            n_contours,
+            n_points,
+            flags,
+            glyphs,
+            composites,
+            bboxes,
+            instructions,
+        ];
+        for stream in streams.iter() {
+            transformed.extend_from_slice(&(stream.len() as u32).to_be_bytes());
+        }
+        for stream in streams.iter() {
+            transformed.extend_from_slice(stream);
+        }
+        transformed
+    }
+
+    /// Transforms the `hmtx` table, omitting all left side bearings.
+    fn transform_hmtx(hmtx: &[u8], num_h_metrics: usize) -> Vec<u8> {
+        let mut transformed = vec![3];
+        for metric in hmtx.chunks(4).take(num_h_metrics) {
+            transformed.extend_from_slice(&metric[..2]);
+        }
+        transformed
+    }
+
+    /// Encodes font data as WOFF2, transforming the `glyf` and `loca` tables
+    /// unless `null_transform`, and the `hmtx` table if `omit_lsbs`.
+    fn encode_woff2(sfnt: &[u8], null_transform: bool, omit_lsbs: bool) -> Vec<u8> {
+        let tables = tables(sfnt);
+        let head = table(&tables, b"head");
+        let index_format = u16::from_be_bytes([head[50], head[51]]);
+        let hhea = table(&tables, b"hhea");
+        let num_h_metrics = u16::from_be_bytes([hhea[34], hhea[35]]) as usize;
+
+        let mut directory = Vec::new();
+        let mut stream = Vec::new();
+        for &(tag, data) in &tables {
+            let (version, transformed) = match &tag {
+                b"glyf" | b"loca" if null_transform => (3, None),
+                b"glyf" => {
+                    let loca = table(&tables, b"loca");
+                    (0, Some(transform_glyf(data, loca, index_format)))
+                }
+                b"loca" => (0, Some(Vec::new())),
+                b"hmtx" if omit_lsbs => (1, Some(transform_hmtx(data, num_h_metrics))),
+                _ => (0, None),
+            };
+            match KNOWN_TAGS.iter().position(|known| **known == tag) {
+                Some(index) => directory.push(index as u8 | version << 6),
+                None => {
+                    directory.push(ARBITRARY_TAG | version << 6);
+                    directory.extend_from_slice(&tag);
+                }
+            }
+            write_base128(&mut directory, data.len() as u32);
+            match transformed {
+                Some(transformed) => {
+                    write_base128(&mut directory, transformed.len() as u32);
+                    stream.extend_from_slice(&transformed);
+                }
+                None => stream.extend_from_slice(data),
+            }
+        }
+
+        let mut compressor = brotli::CompressorWriter::new(Vec::new(), 4096, 9, 22);
+        compressor.write_all(&stream).unwrap();
+        let compressed = compressor.into_inner();
+
+        let mut woff2 = Vec::new();
+        woff2.extend_from_slice(b"wOF2");
+        woff2.extend_from_slice(&sfnt[..4]);
+        let len = WOFF2_HEADER_LEN + directory.len() + compressed.len();
+        woff2.extend_from_slice(&(len as u32).to_be_bytes());
+        woff2.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+        woff2.extend_from_slice(&[0; 2]);
+        woff2.extend_from_slice(&(sfnt.len() as u32).to_be_bytes());
+        woff2.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        woff2.extend_from_slice(&[0; 24]);
+        woff2.extend_from_slice(&directory);
+        woff2.extend_from_slice(&compressed);
+        woff2
+    }
+
+    #[test]
+    fn test_decode_woff2() {
+        let original = tables(NOTOSANS_DATA);
+        let face = crate::parse_font(NOTOSANS_DATA, 0).unwrap();
+        let mut font = crate::font_from_slice(NOTOSANS_DATA, 0).unwrap();
+        for &(null_transform, omit_lsbs) in &[(false, true), (false, false), (true, false)] {
+            let woff2 = encode_woff2(NOTOSANS_DATA, null_transform, omit_lsbs);
+            assert!(woff2.len() < NOTOSANS_DATA.len());
+            assert_eq!(
+                crate::parse_font(&woff2, 0).unwrap_err(),
+                FontError::UndecodedWoff
+            );
+
+            let data = decode_woff(&woff2).unwrap();
+            let decoded = tables(&data);
+            for &(tag, table) in &original {
+                // Glyphs may be encoded differently, but have the same
+                // outlines.
+                if &tag != b"glyf" && &tag != b"loca" {
+                    assert_eq!(self::table(&decoded, &tag), table);
+                }
+            }
+            let decoded_face = crate::parse_font(&data, 0).unwrap();
+            for id in 0..face.number_of_glyphs() {
+                let id = ttf_parser::GlyphId(id);
+                assert_eq!(
+                    decoded_face.glyph_bounding_box(id),
+                    face.glyph_bounding_box(id)
+                );
+            }
+            let mut decoded_font = crate::font_from_slice(&data, 0).unwrap();
+            for c in "badgen 0.1".chars() {
+                let path = font.render_glyph(c).unwrap().path.map(String::from);
+                assert_eq!(
+                    decoded_font.render_glyph(c).unwrap().path.map(String::from),
+                    path
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_woff2_errors() {
+        let woff2 = encode_woff2(NOTOSANS_DATA, false, true);
+        let malformed = |woff2: &[u8]| decode_woff(woff2).unwrap_err() == FontError::MalformedWoff;
+        assert!(malformed(&woff2[..woff2.len() / 2]));
+        assert!(malformed(&woff2[..WOFF2_HEADER_LEN]));
+
+        // A truncated compressed stream.
+        let mut truncated = woff2.clone();
+        let compressed_len = u32::from_be_bytes([woff2[20], woff2[21], woff2[22], woff2[23]]);
+        truncated[20..24].copy_from_slice(&(compressed_len / 2).to_be_bytes());
+        assert!(malformed(&truncated));
+
+        // No tables.
+        let mut empty = woff2.clone();
+        empty[12..14].copy_from_slice(&[0; 2]);
+        assert!(malformed(&empty));
+
+        // The `cmap` table, first in the directory, can't be transformed.
+        let mut transformed = woff2.clone();
+        transformed[WOFF2_HEADER_LEN] |= 1 << 6;
+        assert!(malformed(&transformed));
+
+        // Left side bearings can't be omitted without a transformed `glyf`
+        // table.
+        assert!(malformed(&encode_woff2(NOTOSANS_DATA, true, true)));
+    }
+
+    #[test]
+    fn test_reader_integers() {
+        let base128 = |data: &[u8]| Reader::new(data).base128();
+        assert_eq!(base128(&[0x3f]), Ok(0x3f));
+        assert_eq!(base128(&[0x81, 0x00]), Ok(0x80));
+        assert_eq!(base128(&[0x8f, 0xff, 0xff, 0xff, 0x7f]), Ok(u32::MAX));
+        assert_eq!(base128(&[0x80, 0x01]), Err(FontError::MalformedWoff));
+        assert_eq!(
+            base128(&[0x90, 0x80, 0x80, 0x80, 0x00]),
+            Err(FontError::MalformedWoff)
+        );
+        assert_eq!(
+            base128(&[0x81, 0x80, 0x80, 0x80, 0x80, 0x00]),
+            Err(FontError::MalformedWoff)
+        );
+
+        let u255_16 = |data: &[u8]| Reader::new(data).u255_16();
+        assert_eq!(u255_16(&[252]), Ok(252));
+        assert_eq!(u255_16(&[255, 0]), Ok(253));
+        assert_eq!(u255_16(&[254, 0]), Ok(506));
+        assert_eq!(u255_16(&[253, 0x12, 0x34]), Ok(0x1234));
+        assert_eq!(u255_16(&[253, 0x12]), Err(FontError::MalformedWoff));
+    }
+}
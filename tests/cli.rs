@@ -0,0 +1,121 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+fn badgen(args: &[&str], stdin: Option<&str>) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_badgen"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut child_stdin = child.stdin.take().unwrap();
+    if let Some(stdin) = stdin {
+        child_stdin.write_all(stdin.as_bytes()).unwrap();
+    }
+    drop(child_stdin);
+    child.wait_with_output().unwrap()
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("badgen-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn expected(color: badgen::Color<'_>, status: &str, label: &str) -> String {
+    let mut style = badgen::Style::flat();
+    style.background = color;
+    badgen::badge(&style, status, Some(label)).unwrap()
+}
+
+#[test]
+fn test_cli_args() {
+    let output = badgen(
+        &[
+            "--label", "build", "--status", "passing", "--color", "green", "--style", "flat",
+        ],
+        None,
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        expected(badgen::Color::Green, "passing", "build")
+    );
+
+    let output = badgen(&["--label", "build"], None);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("missing --status"));
+}
+
+#[test]
+fn test_cli_json_png() {
+    let dir = temp_dir("json");
+    let out = dir.join("build.png");
+    let spec = r#"{"label": "build", "status": "passing", "color": "green"}"#;
+    let output = badgen(
+        &[
+            "--json",
+            "--png",
+            "--scale",
+            "2",
+            "-o",
+            out.to_str().unwrap(),
+        ],
+        Some(spec),
+    );
+    assert!(output.status.success());
+    let png = fs::read(&out).unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+    // The height of the image in the IHDR chunk.
+    assert_eq!(u32::from_be_bytes([png[20], png[21], png[22], png[23]]), 40);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_batch() {
+    let dir = temp_dir("batch");
+    let manifest = dir.join("badges.toml");
+    fs::write(
+        &manifest,
+        r#"
+            [[badges]]
+            name = "build"
+            label = "build"
+            status = "passing"
+            color = "green"
+            style = "flat"
+
+            [[badges]]
+            name = "license"
+            label = "license"
+            status = "MIT"
+            style = "shields"
+        "#,
+    )
+    .unwrap();
+    let out_dir = dir.join("out");
+    let output = badgen(
+        &[
+            "--batch",
+            manifest.to_str().unwrap(),
+            "--out-dir",
+            out_dir.to_str().unwrap(),
+        ],
+        None,
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(out_dir.join("build.svg")).unwrap(),
+        expected(badgen::Color::Green, "passing", "build")
+    );
+    // Shields badges are rendered with DejaVu Sans.
+    assert_eq!(
+        fs::read_to_string(out_dir.join("license.svg")).unwrap(),
+        badgen::badge(&badgen::Style::shields(), "MIT", Some("license")).unwrap()
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,103 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+
+struct TestServer {
+    child: Child,
+    addr: String,
+}
+
+impl TestServer {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_badgen-server"))
+            .args(["--addr", "127.0.0.1:0", "--max-age", "60", "--threads", "2"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let addr = line
+            .trim()
+            .strip_prefix("listening on http://")
+            .unwrap()
+            .to_owned();
+        Self { child, addr }
+    }
+
+    fn get(&self, path: &str, headers: &[(&str, &str)]) -> (u16, String, String) {
+        let mut stream = TcpStream::connect(&self.addr).unwrap();
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n",
+            path
+        )
+        .unwrap();
+        for (name, value) in headers {
+            write!(stream, "{}: {}\r\n", name, value).unwrap();
+        }
+        stream.write_all(b"\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head[9..12].parse().unwrap();
+        (status, head.to_owned(), body.to_owned())
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.lines().find_map(|line| {
+        let (field, value) = line.split_once(':')?;
+        if field.eq_ignore_ascii_case(name) {
+            Some(value.trim())
+        } else {
+            None
+        }
+    })
+}
+
+#[test]
+fn test_server() {
+    let server = TestServer::start();
+
+    let (status, _, body) = server.get("/health", &[]);
+    assert_eq!(status, 200);
+    assert_eq!(body, "ok");
+
+    let (status, head, body) = server.get("/badge/build/passing/green", &[]);
+    assert_eq!(status, 200);
+    assert_eq!(
+        header(&head, "Content-Type"),
+        Some("image/svg+xml; charset=utf-8")
+    );
+    assert_eq!(header(&head, "Cache-Control"), Some("public, max-age=60"));
+    assert!(body.starts_with("<svg"));
+    let mut style = badgen::Style::classic();
+    style.background = badgen::Color::Green;
+    assert_eq!(
+        body,
+        badgen::badge(&style, "passing", Some("build")).unwrap()
+    );
+
+    let etag = header(&head, "ETag").unwrap().to_owned();
+    assert!(etag.starts_with('"') && etag.ends_with('"'));
+    let (status, head, body) =
+        server.get("/badge/build/passing/green", &[("If-None-Match", &etag)]);
+    assert_eq!(status, 304);
+    assert_eq!(header(&head, "ETag"), Some(etag.as_str()));
+    assert!(body.is_empty());
+
+    let (status, _, _) = server.get("/badge/build/passing/nope", &[]);
+    assert_eq!(status, 400);
+
+    let (status, _, _) = server.get("/nope", &[]);
+    assert_eq!(status, 404);
+}